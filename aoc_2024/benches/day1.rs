@@ -0,0 +1,21 @@
+use aoc_2024::day1::get_location_data;
+use common::{bench, black_box, BenchConfig, InputLocator};
+
+fn main() -> anyhow::Result<()> {
+    let input = InputLocator::new(env!("CARGO_MANIFEST_DIR")).read(1, None)?;
+    let config = BenchConfig::default();
+
+    let stats = bench(&config, || get_location_data(black_box(&input)));
+    println!("get_location_data:          {stats}");
+
+    let location_data = get_location_data(&input)?;
+    let location_data = black_box(&location_data);
+    let stats = bench(&config, || location_data.sum_of_diffs());
+    println!("sum_of_diffs:               {stats}");
+    let stats = bench(&config, || location_data.similarity_score());
+    println!("similarity_score:           {stats}");
+    let stats = bench(&config, || location_data.similarity_score_optimized());
+    println!("similarity_score_optimized: {stats}");
+
+    Ok(())
+}
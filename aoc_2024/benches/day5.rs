@@ -0,0 +1,26 @@
+use aoc_2024::day5::{PrintInput, RuleLookup};
+use common::{bench, black_box, BenchConfig, InputLocator};
+
+fn main() -> anyhow::Result<()> {
+    let input = InputLocator::new(env!("CARGO_MANIFEST_DIR")).read(5, None)?;
+    let config = BenchConfig::default();
+
+    let stats = bench(&config, || black_box(&input).parse::<PrintInput>());
+    println!("{:<45} {stats}", "parse:");
+
+    for (name, rule_lookup) in [
+        ("hash set", RuleLookup::HashSet),
+        ("bitset", RuleLookup::Bitset),
+    ] {
+        let print_input = input.parse::<PrintInput>()?.with_rule_lookup(rule_lookup);
+        let print_input = black_box(&print_input);
+        let stats = bench(&config, || print_input.sum_of_valid_update_middle_page());
+        let label = format!("sum_of_valid_update_middle_page ({name}):");
+        println!("{label:<45} {stats}");
+        let stats = bench(&config, || print_input.sum_of_invalid_update_middle_page());
+        let label = format!("sum_of_invalid_update_middle_page ({name}):");
+        println!("{label:<45} {stats}");
+    }
+
+    Ok(())
+}
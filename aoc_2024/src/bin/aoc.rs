@@ -0,0 +1,336 @@
+use anyhow::{bail, Context};
+use aoc_2024::day5::Day5;
+use aoc_2024::day6::render::{Patrol, Style};
+use aoc_2024::day6::{Day6, Position};
+use aoc_2024::report::{render_json, render_table, DayReport};
+use aoc_2024::verify::{verify, ExpectedAnswers, Verdict, ANSWERS_FILE};
+use aoc_2024::{Solver, SOLVERS};
+use clap::{Parser, Subcommand};
+use common::normalize::normalize;
+use common::{InputLocator, InputSource, Part, Solution};
+use std::fs;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(about = "Advent of code 2024 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solvers for one day, a range of days (e.g. `1-3`) or the whole year
+    Run {
+        /// Day or inclusive range of days to run; runs every solved day when omitted
+        #[arg(short, long, value_parser = parse_days)]
+        day: Option<RangeInclusive<u8>>,
+        /// Part to run; runs both parts when omitted
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file to use instead of the day's default input, or `-` to read from stdin
+        #[arg(short, long)]
+        input: Option<InputSource>,
+        /// Print the answers and per-stage timings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the answers of every solver on its real input against the expected answers
+    Verify {
+        /// Day or inclusive range of days to verify; verifies every solved day when omitted
+        #[arg(short, long, value_parser = parse_days)]
+        day: Option<RangeInclusive<u8>>,
+        /// Expected answers file; defaults to `answers.toml` next to the inputs
+        #[arg(short, long)]
+        answers: Option<PathBuf>,
+    },
+    /// Explain which ordering rules each invalid day 5 page update breaks
+    Day5Violations {
+        /// Input file to use instead of the day's default input, or `-` to read from stdin
+        #[arg(short, long)]
+        input: Option<InputSource>,
+    },
+    /// List every position where a new obstruction traps the day 6 guard in a loop
+    Day6Loops {
+        /// Input file to use instead of the day's default input, or `-` to read from stdin
+        #[arg(short, long)]
+        input: Option<InputSource>,
+        /// Also dump the corners of the loop each obstruction causes
+        #[arg(long)]
+        cycles: bool,
+    },
+    /// Report where every day 6 guard goes when patrolling on its own
+    Day6Guards {
+        /// Input file to use instead of the day's default input, or `-` to read from stdin
+        #[arg(short, long)]
+        input: Option<InputSource>,
+    },
+    /// Draw the day 6 guard's patrol over the map
+    Day6Patrol {
+        /// Input file to use instead of the day's default input, or `-` to read from stdin
+        #[arg(short, long)]
+        input: Option<InputSource>,
+        /// Index of the guard to follow, in reading order
+        #[arg(short, long, default_value_t = 0)]
+        guard: usize,
+        /// Extra obstruction to add, as `row,column`
+        #[arg(short, long, value_parser = parse_position)]
+        obstruction: Option<Position>,
+        /// Colour the output with ANSI escape codes
+        #[arg(long)]
+        color: bool,
+        /// Animate the patrol step by step in the terminal
+        #[arg(long)]
+        animate: bool,
+        /// Delay between animation frames in milliseconds
+        #[arg(long, default_value_t = 50)]
+        delay: u64,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            json,
+        } => run(day, part, input, json),
+        Command::Verify { day, answers } => verify_answers(day, answers),
+        Command::Day5Violations { input } => day5_violations(input),
+        Command::Day6Loops { input, cycles } => day6_loops(input, cycles),
+        Command::Day6Guards { input } => day6_guards(input),
+        Command::Day6Patrol {
+            input,
+            guard,
+            obstruction,
+            color,
+            animate,
+            delay,
+        } => {
+            let style = if color { Style::Ansi } else { Style::Plain };
+            let delay = animate.then_some(Duration::from_millis(delay));
+            day6_patrol(input, guard, obstruction, style, delay)
+        }
+    }
+}
+
+fn run(
+    days: Option<RangeInclusive<u8>>,
+    part: Option<u8>,
+    input: Option<InputSource>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let solvers = select_solvers(days)?;
+    if input.is_some() && solvers.len() > 1 {
+        bail!("an explicit input can only be used when running a single day");
+    }
+    let locator = InputLocator::new(env!("CARGO_MANIFEST_DIR"));
+    let parts = match part {
+        Some(part) => vec![Part::try_from(part)?],
+        None => Part::ALL.to_vec(),
+    };
+    let mut reports = Vec::with_capacity(solvers.len());
+    for solver in solvers {
+        let report = match (&input, solver.solve_reader) {
+            (Some(InputSource::Stdin), Some(solve_reader)) => {
+                solve_reader(&mut io::stdin().lock(), &parts)
+            }
+            _ => {
+                let input = locator.read(solver.day, input.as_ref())?;
+                (solver.solve)(&input, &parts)
+            }
+        }
+        .with_context(|| format!("day {} failed", solver.day))?;
+        if !json {
+            println!("day {} parse took {:.2?}", solver.day, report.parse_elapsed);
+            for answer in &report.answers {
+                println!(
+                    "day {} part {}: {}; took {:.2?}",
+                    solver.day, answer.part, answer.answer, answer.elapsed
+                );
+            }
+        }
+        reports.push(DayReport {
+            day: solver.day,
+            report,
+        });
+    }
+    if json {
+        println!("{}", render_json(&reports));
+    } else if reports.len() > 1 {
+        println!();
+        print!("{}", render_table(&reports));
+    }
+    Ok(())
+}
+
+fn verify_answers(
+    days: Option<RangeInclusive<u8>>,
+    answers: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let solvers = select_solvers(days)?;
+    let locator = InputLocator::new(env!("CARGO_MANIFEST_DIR"));
+    let answers_path = match answers {
+        Some(path) => path,
+        None => locator.locate(ANSWERS_FILE)?,
+    };
+    let expected = fs::read_to_string(&answers_path)
+        .with_context(|| format!("Could not read {}", answers_path.display()))?
+        .parse::<ExpectedAnswers>()?;
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for solver in solvers {
+        let report = locator
+            .read(solver.day, None)
+            .and_then(|input| (solver.solve)(&input, &Part::ALL));
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                println!("day {}: ERROR {:#}", solver.day, e);
+                failed += Part::ALL.len();
+                continue;
+            }
+        };
+        for part_verdict in verify(solver.day, &report, &expected) {
+            match part_verdict.verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { .. } => failed += 1,
+                Verdict::Missing => missing += 1,
+            }
+            println!(
+                "day {} part {}: {} -> {}",
+                solver.day, part_verdict.part, part_verdict.answer, part_verdict.verdict
+            );
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if failed > 0 {
+        bail!("{failed} answer(s) did not match the expected answers");
+    }
+    Ok(())
+}
+
+fn day5_violations(input: Option<InputSource>) -> anyhow::Result<()> {
+    let print_input = load::<Day5>(5, input)?;
+    let invalid_updates = print_input.invalid_updates();
+    for invalid_update in &invalid_updates {
+        println!("{invalid_update}");
+    }
+    println!("{} invalid update(s)", invalid_updates.len());
+    Ok(())
+}
+
+fn day6_loops(input: Option<InputSource>, cycles: bool) -> anyhow::Result<()> {
+    let lab_input = load::<Day6>(6, input)?;
+    let looping_obstructions = lab_input.looping_obstructions();
+    for looping_obstruction in &looping_obstructions {
+        if cycles {
+            println!("{looping_obstruction:#}");
+        } else {
+            println!("{looping_obstruction}");
+        }
+    }
+    println!("{} looping obstruction(s)", looping_obstructions.len());
+    Ok(())
+}
+
+fn day6_guards(input: Option<InputSource>) -> anyhow::Result<()> {
+    let lab_input = load::<Day6>(6, input)?;
+    let reports = lab_input.guard_reports();
+    for report in &reports {
+        println!("{report}");
+    }
+    println!("{} guard(s)", reports.len());
+    Ok(())
+}
+
+/// Prints the patrol, animating it with `delay` between frames when given.
+fn day6_patrol(
+    input: Option<InputSource>,
+    guard: usize,
+    obstruction: Option<Position>,
+    style: Style,
+    delay: Option<Duration>,
+) -> anyhow::Result<()> {
+    let lab_input = load::<Day6>(6, input)?;
+    let guards = lab_input.guards();
+    let Some(&guard) = guards.get(guard) else {
+        bail!(
+            "no guard {guard}, the map only has {} guard(s)",
+            guards.len()
+        );
+    };
+    let patrol = Patrol::for_guard(&lab_input, guard, obstruction)?;
+    if let Some(delay) = delay {
+        for steps in 0..=patrol.steps().len() {
+            // Clear the screen and move the cursor back to the top left before every frame
+            print!("\x1b[2J\x1b[H{}", patrol.render_frame(steps, style));
+            io::stdout().flush()?;
+            thread::sleep(delay);
+        }
+    } else {
+        print!("{}", patrol.render(style));
+    }
+    let outcome = if patrol.is_loop() {
+        "gets stuck in a loop"
+    } else {
+        "leaves the map"
+    };
+    println!("the guard {outcome} after {} steps", patrol.steps().len());
+    Ok(())
+}
+
+/// Reads and parses the input of `day` the same way [`common::solve`] does.
+fn load<S: Solution>(day: u8, input: Option<InputSource>) -> anyhow::Result<S::Input> {
+    let locator = InputLocator::new(env!("CARGO_MANIFEST_DIR"));
+    let input = locator.read(day, input.as_ref())?;
+    S::parse(&normalize(&input))
+}
+
+fn select_solvers(days: Option<RangeInclusive<u8>>) -> anyhow::Result<Vec<&'static Solver>> {
+    let solvers = match days {
+        Some(days) => SOLVERS
+            .iter()
+            .filter(|solver| days.contains(&solver.day))
+            .collect::<Vec<_>>(),
+        None => SOLVERS.iter().collect(),
+    };
+    if solvers.is_empty() {
+        bail!("no solver registered for the requested day(s)");
+    }
+    Ok(solvers)
+}
+
+fn parse_position(s: &str) -> Result<Position, String> {
+    let (row, col) = s
+        .split_once(',')
+        .ok_or_else(|| format!("expected `row,column`, got `{s}`"))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|e| format!("invalid coordinate `{value}`: {e}"))
+    };
+    Ok(Position::new(parse(row)?, parse(col)?))
+}
+
+fn parse_days(s: &str) -> Result<RangeInclusive<u8>, String> {
+    let parse_day = |day: &str| {
+        day.trim()
+            .parse::<u8>()
+            .map_err(|e| format!("invalid day `{day}`: {e}"))
+    };
+    match s.split_once('-') {
+        Some((start, end)) => Ok(parse_day(start)?..=parse_day(end)?),
+        None => {
+            let day = parse_day(s)?;
+            Ok(day..=day)
+        }
+    }
+}
@@ -1,19 +1,26 @@
-use anyhow::{bail, Context};
-use itertools::Itertools;
+use common::parse::ParseError;
+use common::parser::{integer, lines_of, parse_all, separated_pair, space1};
+use common::Solution;
 use std::collections::HashMap;
-use std::fs;
+use std::fmt::Display;
 use std::str::FromStr;
 
-fn main() -> anyhow::Result<()> {
-    let input_data = fs::read_to_string("inputs/day1.txt").context("Could not read input")?;
-    let location_data = get_location_data(&input_data)?;
-    println!("distance between lists {}", location_data.sum_of_diffs());
-    println!("similarity score is {}", location_data.similarity_score());
-    println!(
-        "similarity score(optimized) is {}",
-        location_data.similarity_score_optimized()
-    );
-    Ok(())
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = SortedLocationData;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        get_location_data(input)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.sum_of_diffs())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.similarity_score_optimized())
+    }
 }
 
 type LocationId = usize;
@@ -25,12 +32,12 @@ struct LocationData {
 }
 
 #[derive(Debug)]
-struct SortedLocationData {
+pub struct SortedLocationData {
     left: Vec<LocationId>,
     right: Vec<LocationId>,
 }
 
-fn get_location_data(input_data: &str) -> anyhow::Result<SortedLocationData> {
+pub fn get_location_data(input_data: &str) -> anyhow::Result<SortedLocationData> {
     let location_data = LocationData::from_str(input_data)?;
     Ok(SortedLocationData::from(location_data))
 }
@@ -47,29 +54,24 @@ impl From<LocationData> for SortedLocationData {
 }
 
 impl FromStr for LocationData {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let outcome: Result<LocationData, Self::Err> = s.split_whitespace().tuples().try_fold(
-            LocationData::default(),
-            |mut data, (left, right)| {
-                let left_location = left.parse().context("failed to parse left location")?;
-                let right_location = right.parse().context("failed to parse right location")?;
-                data.left.push(left_location);
-                data.right.push(right_location);
-                Ok(data)
-            },
-        );
-        let outcome = outcome?;
-        if outcome.left.len() != outcome.right.len() {
-            bail!("parse error: left location count != right location count");
-        }
-        Ok(outcome)
+        let pairs = parse_all(
+            s,
+            lines_of(separated_pair(
+                integer::<LocationId>(),
+                space1(),
+                integer::<LocationId>(),
+            )),
+        )?;
+        let (left, right) = pairs.into_iter().unzip();
+        Ok(LocationData { left, right })
     }
 }
 
 impl SortedLocationData {
-    fn sum_of_diffs(&self) -> usize {
+    pub fn sum_of_diffs(&self) -> usize {
         self.left
             .iter()
             .zip(self.right.iter())
@@ -77,7 +79,7 @@ impl SortedLocationData {
             .sum()
     }
 
-    fn similarity_score(&self) -> usize {
+    pub fn similarity_score(&self) -> usize {
         let count_map = self.right.iter().fold(HashMap::new(), |mut map, loc| {
             let cnt = map.entry(loc).or_insert(0);
             *cnt += 1;
@@ -89,7 +91,7 @@ impl SortedLocationData {
             .sum()
     }
 
-    fn similarity_score_optimized(&self) -> usize {
+    pub fn similarity_score_optimized(&self) -> usize {
         let left_values = self.left.as_slice();
         let right_values = self.right.as_slice();
         let (mut left_ptr, mut right_ptr) = (0, 0);
@@ -102,6 +104,9 @@ impl SortedLocationData {
             while left_ptr < sz && left_values[left_ptr] < right_values[right_ptr] {
                 left_ptr += 1;
             }
+            if left_ptr == sz {
+                break;
+            }
             // skip values on the right less than the current left value
             while right_ptr < sz && right_values[right_ptr] < left_values[left_ptr] {
                 right_ptr += 1;
@@ -112,7 +117,7 @@ impl SortedLocationData {
                 dup_occurrences_of_left_val += 1;
             }
             // count occurrences of current left value on the right side
-            while left_values[left_ptr] == right_values[right_ptr] {
+            while right_ptr < sz && left_values[left_ptr] == right_values[right_ptr] {
                 cnt_of_left_val_on_right += 1;
                 right_ptr += 1;
             }
@@ -147,6 +152,16 @@ mod tests {
         assert_eq!(sorted_location_data.right, vec![3, 3, 3, 4, 5, 9]);
     }
 
+    #[test]
+    fn test_get_location_data_reports_error_location() {
+        let input = "3   4\n4 3\n2";
+
+        let err = LocationData::from_str(input).unwrap_err();
+
+        assert_eq!((err.line, err.column), (3, 2));
+        assert_eq!(err.snippet, "2");
+    }
+
     #[test]
     fn test_sum_of_diffs() {
         let input = SortedLocationData {
@@ -166,4 +181,32 @@ mod tests {
 
         assert_eq!(input.similarity_score_optimized(), 31);
     }
+
+    #[test]
+    fn test_similarity_score_optimized_at_list_ends() {
+        // The largest values are equal, so the right list runs out while counting
+        let input = SortedLocationData {
+            left: vec![1, 5, 5],
+            right: vec![2, 5, 5],
+        };
+        assert_eq!(input.similarity_score_optimized(), 20);
+        assert_eq!(input.similarity_score(), 20);
+
+        // The left list runs out while skipping past smaller values
+        let input = SortedLocationData {
+            left: vec![1, 2],
+            right: vec![2, 9],
+        };
+        assert_eq!(input.similarity_score_optimized(), 2);
+        assert_eq!(input.similarity_score(), 2);
+
+        for (input, expected) in [("1 1", 1), ("1 2", 0)] {
+            assert_eq!(
+                get_location_data(input)
+                    .unwrap()
+                    .similarity_score_optimized(),
+                expected
+            );
+        }
+    }
 }
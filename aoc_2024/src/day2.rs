@@ -1,43 +1,41 @@
-use anyhow::Context;
+use common::parse::ParseError;
+use common::parser::{integer, lines_of, parse_all, separated, space1};
+use common::Solution;
 use itertools::Itertools;
-use std::fs;
+use std::fmt::Display;
 use std::str::FromStr;
 
-fn main() -> anyhow::Result<()> {
-    let input_data = fs::read_to_string("inputs/day2.txt").context("Could not read input data")?;
-    let report_data = ReportData::from_str(input_data.as_str())?;
-    println!(
-        "There are {} safe report entries",
-        report_data.num_of_safe_reports()
-    );
-    println!(
-        "There are {} safe report entries when factoring in problem dampener",
-        report_data.num_of_safe_reports_with_dampener()
-    );
-    Ok(())
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = ReportData;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(ReportData::from_str(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.num_of_safe_reports())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.num_of_safe_reports_with_dampener())
+    }
 }
 
 type Report = Vec<i32>;
 
 impl FromStr for ReportData {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let reports = s
-            .split('\n')
-            .map(|line| {
-                line.trim()
-                    .split(' ')
-                    .map(|level| level.parse::<i32>().context("Could not parse level"))
-                    .collect::<Result<Report, anyhow::Error>>()
-            })
-            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        let reports = parse_all(s, lines_of(separated(integer(), space1())))?;
         Ok(ReportData { reports })
     }
 }
 
 #[derive(Debug, Default, PartialOrd, PartialEq)]
-struct ReportData {
+pub struct ReportData {
     reports: Vec<Report>,
 }
 
@@ -79,7 +77,7 @@ impl ReportData {
 
 #[cfg(test)]
 mod tests {
-    use crate::{is_safe, ReportData};
+    use super::{is_safe, ReportData};
     use std::str::FromStr;
 
     #[test]
@@ -118,6 +116,28 @@ mod tests {
         assert_eq!(expected, report_data);
     }
 
+    #[test]
+    fn test_from_str_report_data_tolerates_messy_input() {
+        let s = "7 6  4 2 1\r\n1 2 7 8\n\n";
+        let expected = ReportData {
+            reports: vec![vec![7, 6, 4, 2, 1], vec![1, 2, 7, 8]],
+        };
+
+        let report_data = ReportData::from_str(s).expect("Could not parse test data");
+
+        assert_eq!(expected, report_data);
+    }
+
+    #[test]
+    fn test_from_str_report_data_reports_error_location() {
+        let s = "7 6 4 2 1\n1 2 x 8";
+
+        let err = ReportData::from_str(s).unwrap_err();
+
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(err.snippet, "1 2 x 8");
+    }
+
     #[test]
     fn test_num_of_safe_reports() {
         let report_data = ReportData {
@@ -0,0 +1,514 @@
+use anyhow::Context;
+use common::{Part, Solution, SolveReport};
+use std::fmt::{self, Debug, Display};
+use std::io::{self, BufReader, Read};
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    /// Only the instructions are kept, not the corrupted memory around them.
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(lex(INSTRUCTIONS, input.bytes())
+            .map(|(_, instruction)| instruction)
+            .collect())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(execute_all(Interpreter::new(false), input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(execute_all(Interpreter::new(true), input))
+    }
+}
+
+/// Solves day 3 reading the input from `reader`, e.g. stdin, without holding it in memory.
+pub fn solve_reader(reader: &mut dyn Read, parts: &[Part]) -> anyhow::Result<SolveReport> {
+    common::solve_with::<Day3>(
+        || {
+            read_bytes(reader, |bytes| {
+                lex(INSTRUCTIONS, bytes)
+                    .map(|(_, instruction)| instruction)
+                    .collect()
+            })
+            .context("Could not read input")
+        },
+        parts,
+    )
+}
+
+/// Feeds the bytes read from `reader` to `consume`, stopping at the first read error rather
+/// than collecting the bytes up front.
+fn read_bytes<T>(
+    reader: impl Read,
+    consume: impl FnOnce(&mut dyn Iterator<Item = u8>) -> T,
+) -> io::Result<T> {
+    let mut error = None;
+    let mut bytes = BufReader::new(reader)
+        .bytes()
+        .map_while(|byte| byte.map_err(|e| error = Some(e)).ok());
+    let output = consume(&mut bytes);
+    error.map_or(Ok(output), Err)
+}
+
+/// An instruction recognised in the corrupted memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(usize, usize),
+    Do,
+    Dont,
+}
+
+pub const MAX_ARITY: usize = 4;
+const MAX_DIGITS: u8 = 3;
+
+/// Describes how an instruction is written: `name(arg,...)` with exactly `arity` arguments,
+/// each a number of one to three digits. `build` turns the arguments into the instruction.
+pub struct InstructionSpec<I> {
+    pub name: &'static str,
+    pub arity: usize,
+    pub build: fn(&[usize]) -> I,
+}
+
+impl<I> InstructionSpec<I> {
+    fn prefix_len(&self) -> usize {
+        self.name.len() + 1
+    }
+
+    /// The byte at `idx` of the instruction's name followed by the opening parenthesis.
+    fn prefix_byte(&self, idx: usize) -> u8 {
+        self.name.as_bytes().get(idx).copied().unwrap_or(b'(')
+    }
+}
+
+/// The instructions understood by [`Interpreter`].
+pub const INSTRUCTIONS: &[InstructionSpec<Instruction>] = &[
+    InstructionSpec {
+        name: "mul",
+        arity: 2,
+        build: |args| Instruction::Mul(args[0], args[1]),
+    },
+    InstructionSpec {
+        name: "do",
+        arity: 0,
+        build: |_| Instruction::Do,
+    },
+    InstructionSpec {
+        name: "don't",
+        arity: 0,
+        build: |_| Instruction::Dont,
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    /// The first `matched` bytes of the prefix of instruction `spec` have been seen.
+    Prefix { spec: usize, matched: usize },
+    /// Inside the parentheses of the instruction starting at byte `start`, reading the argument
+    /// at `index`.
+    Args {
+        spec: usize,
+        start: usize,
+        args: [usize; MAX_ARITY],
+        index: usize,
+        digits: u8,
+    },
+}
+
+const START: State = State::Prefix {
+    spec: 0,
+    matched: 0,
+};
+
+/// A single-pass lexer fed one byte at a time, so the input never has to be held in memory.
+/// Which instructions it recognises is entirely driven by its instruction set.
+pub struct Lexer<'s, I> {
+    instructions: &'s [InstructionSpec<I>],
+    state: State,
+    /// Offset of the byte currently being processed.
+    offset: usize,
+}
+
+impl<'s, I> Lexer<'s, I> {
+    pub fn new(instructions: &'s [InstructionSpec<I>]) -> Self {
+        assert!(
+            instructions.iter().all(|spec| spec.arity <= MAX_ARITY),
+            "instructions can take at most {MAX_ARITY} arguments"
+        );
+        Lexer {
+            instructions,
+            state: START,
+            offset: 0,
+        }
+    }
+
+    /// Consumes the next byte of input, returning the instruction it completes along with the
+    /// byte offset the instruction starts at.
+    pub fn push(&mut self, byte: u8) -> Option<(usize, I)> {
+        let completed = self.step(byte);
+        self.offset += 1;
+        completed
+    }
+
+    fn step(&mut self, byte: u8) -> Option<(usize, I)> {
+        match self.state {
+            State::Prefix { spec, matched } => {
+                self.state = self.advance_prefix(spec, matched, byte);
+                None
+            }
+            State::Args {
+                spec,
+                start,
+                args,
+                index,
+                digits,
+            } => self.step_args(spec, start, args, index, digits, byte),
+        }
+    }
+
+    fn step_args(
+        &mut self,
+        spec: usize,
+        start: usize,
+        mut args: [usize; MAX_ARITY],
+        index: usize,
+        digits: u8,
+        byte: u8,
+    ) -> Option<(usize, I)> {
+        let instruction = &self.instructions[spec];
+        let arity = instruction.arity;
+        match byte {
+            b'0'..=b'9' if index < arity && digits < MAX_DIGITS => {
+                args[index] = args[index] * 10 + usize::from(byte - b'0');
+                self.state = State::Args {
+                    spec,
+                    start,
+                    args,
+                    index,
+                    digits: digits + 1,
+                };
+                None
+            }
+            b',' if digits > 0 && index + 1 < arity => {
+                self.state = State::Args {
+                    spec,
+                    start,
+                    args,
+                    index: index + 1,
+                    digits: 0,
+                };
+                None
+            }
+            b')' if (arity == 0 && digits == 0) || (index + 1 == arity && digits > 0) => {
+                self.state = START;
+                Some((start, (instruction.build)(&args[..arity])))
+            }
+            // The malformed instruction is dropped, but this byte may start a new one
+            _ => {
+                self.state = START;
+                self.step(byte)
+            }
+        }
+    }
+
+    /// Extends the partially `matched` prefix of `spec` with `byte`. If no prefix continues that
+    /// way, falls back to the longest suffix of the matched bytes that still starts one.
+    fn advance_prefix(&self, spec: usize, matched: usize, byte: u8) -> State {
+        let seen = &self.instructions[spec];
+        for skipped in 0..=matched {
+            let tail_len = matched - skipped;
+            let continued = self.instructions.iter().position(|candidate| {
+                candidate.prefix_len() > tail_len
+                    && (0..tail_len)
+                        .all(|i| candidate.prefix_byte(i) == seen.prefix_byte(skipped + i))
+                    && candidate.prefix_byte(tail_len) == byte
+            });
+            let Some(spec) = continued else {
+                continue;
+            };
+            let matched = tail_len + 1;
+            if matched < self.instructions[spec].prefix_len() {
+                return State::Prefix { spec, matched };
+            }
+            return State::Args {
+                spec,
+                start: self.offset + 1 - matched,
+                args: [0; MAX_ARITY],
+                index: 0,
+                digits: 0,
+            };
+        }
+        START
+    }
+}
+
+/// Lexes the instructions of `instructions` out of a stream of bytes, along with their offsets.
+pub fn lex<'s, I>(
+    instructions: &'s [InstructionSpec<I>],
+    bytes: impl IntoIterator<Item = u8> + 's,
+) -> impl Iterator<Item = (usize, I)> + 's {
+    let mut lexer = Lexer::new(instructions);
+    bytes.into_iter().filter_map(move |byte| lexer.push(byte))
+}
+
+/// Something that executes instructions. New instruction kinds are added by implementing this
+/// for a machine with its own instruction type and set; the lexer needs no changes.
+pub trait Machine {
+    type Instruction: Debug + 'static;
+
+    /// The instructions recognised in the input.
+    const INSTRUCTIONS: &'static [InstructionSpec<Self::Instruction>];
+
+    /// Executes `instruction`, returning `false` if it was skipped because it is disabled.
+    fn execute(&mut self, instruction: &Self::Instruction) -> bool;
+
+    /// Runs every instruction found in `bytes`.
+    fn run(&mut self, bytes: impl IntoIterator<Item = u8>) {
+        let mut lexer = Lexer::new(Self::INSTRUCTIONS);
+        for byte in bytes {
+            if let Some((_, instruction)) = lexer.push(byte) {
+                self.execute(&instruction);
+            }
+        }
+    }
+
+    /// Runs every instruction found in `bytes`, recording each one as it is executed.
+    fn trace(&mut self, bytes: impl IntoIterator<Item = u8>) -> Vec<TraceEntry<Self::Instruction>> {
+        let mut lexer = Lexer::new(Self::INSTRUCTIONS);
+        let mut trace = Vec::new();
+        for byte in bytes {
+            if let Some((offset, instruction)) = lexer.push(byte) {
+                let enabled = self.execute(&instruction);
+                trace.push(TraceEntry {
+                    offset,
+                    instruction,
+                    enabled,
+                });
+            }
+        }
+        trace
+    }
+
+    /// Runs the instructions read from `reader`, e.g. stdin, without buffering the whole input.
+    fn run_reader(&mut self, reader: impl Read) -> io::Result<()> {
+        read_bytes(reader, |bytes| self.run(bytes))
+    }
+}
+
+/// One executed instruction in a [`Machine::trace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry<I> {
+    /// Byte offset of the instruction in the input.
+    pub offset: usize,
+    pub instruction: I,
+    pub enabled: bool,
+}
+
+impl<I: Debug> Display for TraceEntry<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.enabled { "enabled" } else { "skipped" };
+        write!(f, "{:>8}: {:?} ({status})", self.offset, self.instruction)
+    }
+}
+
+/// Sums the products of the `mul` instructions, keeping track of whether they are enabled.
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    /// Whether `do()` and `don't()` are honoured at all.
+    conditional: bool,
+    enabled: bool,
+    total: usize,
+}
+
+impl Interpreter {
+    pub fn new(conditional: bool) -> Self {
+        Interpreter {
+            conditional,
+            enabled: true,
+            total: 0,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+impl Machine for Interpreter {
+    type Instruction = Instruction;
+
+    const INSTRUCTIONS: &'static [InstructionSpec<Instruction>] = INSTRUCTIONS;
+
+    fn execute(&mut self, instruction: &Instruction) -> bool {
+        match *instruction {
+            Instruction::Mul(a, b) if self.enabled || !self.conditional => self.total += a * b,
+            Instruction::Mul(..) => return false,
+            Instruction::Do => self.enabled = true,
+            Instruction::Dont => self.enabled = false,
+        }
+        true
+    }
+}
+
+fn execute_all(mut interpreter: Interpreter, instructions: &[Instruction]) -> usize {
+    for instruction in instructions {
+        interpreter.execute(instruction);
+    }
+    interpreter.total()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditional_multiply(s: &str) -> usize {
+        let mut interpreter = Interpreter::new(true);
+        interpreter.run(s.bytes());
+        interpreter.total()
+    }
+
+    fn multiply(s: &str) -> usize {
+        let mut interpreter = Interpreter::new(false);
+        interpreter.run(s.bytes());
+        interpreter.total()
+    }
+
+    #[test]
+    fn test_multiply() {
+        let s = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        assert_eq!(multiply(s), 161);
+    }
+
+    #[test]
+    fn test_conditional_multiply() {
+        let s = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(conditional_multiply(s), 48);
+
+        let s = "mul(1,8)undo()?mul(8,2))don't()mul(1,2)do()mul(4,5)don't()mul(6,7)";
+        assert_eq!(conditional_multiply(s), 44);
+    }
+
+    #[test]
+    fn test_lex() {
+        let s = "mmul(1,2)mul(1234,5)mul(1,2,3)mul(mul(3,4)dodon't()do(1)do()";
+        assert_eq!(
+            lex(INSTRUCTIONS, s.bytes()).collect::<Vec<_>>(),
+            vec![
+                (1, Instruction::Mul(1, 2)),
+                (34, Instruction::Mul(3, 4)),
+                (44, Instruction::Dont),
+                (56, Instruction::Do)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let s = "mul(2,3)don't()mul(4,5)";
+        let trace = Interpreter::new(true).trace(s.bytes());
+
+        assert_eq!(
+            trace,
+            vec![
+                TraceEntry {
+                    offset: 0,
+                    instruction: Instruction::Mul(2, 3),
+                    enabled: true
+                },
+                TraceEntry {
+                    offset: 8,
+                    instruction: Instruction::Dont,
+                    enabled: true
+                },
+                TraceEntry {
+                    offset: 15,
+                    instruction: Instruction::Mul(4, 5),
+                    enabled: false
+                },
+            ]
+        );
+        assert_eq!(trace[2].to_string(), "      15: Mul(4, 5) (skipped)");
+    }
+
+    #[test]
+    fn test_solve_reader_matches_solve() -> anyhow::Result<()> {
+        let s = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let streamed = solve_reader(&mut s.as_bytes(), &Part::ALL)?;
+        let buffered = common::solve::<Day3>(s, &Part::ALL)?;
+
+        for part in Part::ALL {
+            assert_eq!(
+                streamed.answer(part).map(|answer| &answer.answer),
+                buffered.answer(part).map(|answer| &answer.answer)
+            );
+        }
+        assert_eq!(streamed.answer(Part::Two).unwrap().answer, "48");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_reader() -> anyhow::Result<()> {
+        let mut interpreter = Interpreter::new(true);
+        interpreter.run_reader("mul(2,3)don't()mul(4,5)do()mul(1,1)".as_bytes())?;
+
+        assert_eq!(interpreter.total(), 7);
+
+        Ok(())
+    }
+
+    /// A machine with its own instruction set, to check new kinds need no lexer changes.
+    #[derive(Default)]
+    struct Accumulator {
+        value: i64,
+        scale: i64,
+    }
+
+    #[derive(Debug)]
+    enum AccumulatorInstruction {
+        Add(usize, usize),
+        Neg,
+        Scale(usize),
+    }
+
+    impl Machine for Accumulator {
+        type Instruction = AccumulatorInstruction;
+
+        const INSTRUCTIONS: &'static [InstructionSpec<AccumulatorInstruction>] = &[
+            InstructionSpec {
+                name: "add",
+                arity: 2,
+                build: |args| AccumulatorInstruction::Add(args[0], args[1]),
+            },
+            InstructionSpec {
+                name: "neg",
+                arity: 0,
+                build: |_| AccumulatorInstruction::Neg,
+            },
+            InstructionSpec {
+                name: "scale",
+                arity: 1,
+                build: |args| AccumulatorInstruction::Scale(args[0]),
+            },
+        ];
+
+        fn execute(&mut self, instruction: &AccumulatorInstruction) -> bool {
+            match *instruction {
+                AccumulatorInstruction::Add(a, b) => self.value += self.scale * (a + b) as i64,
+                AccumulatorInstruction::Neg => self.value = -self.value,
+                AccumulatorInstruction::Scale(n) => self.scale = n as i64,
+            }
+            true
+        }
+    }
+
+    #[test]
+    fn test_custom_machine() {
+        let mut machine = Accumulator::default();
+        machine.run("scale(2)add(1,2)xneg()add(3)scale(1)add(10,0)mul(9,9)".bytes());
+
+        assert_eq!(machine.value, 4);
+    }
+}
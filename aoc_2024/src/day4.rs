@@ -0,0 +1,432 @@
+use aho_corasick::AhoCorasick;
+use anyhow::{ensure, Context};
+use common::geom::{Direction8, Offset};
+use common::grid::{self, Coord};
+use common::parse::ParseError;
+use common::Solution;
+use std::fmt::Display;
+use std::str::FromStr;
+
+const XMAS: &[u8] = "XMAS".as_bytes();
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Grid;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Grid::from_str(input).context("Could not parse input")
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.find_xmas_count(XMAS))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.find_x_mas_count())
+    }
+}
+
+/// The X-MAS cross: two `MAS` diagonals crossing at the `A`, read in any direction.
+const X_MAS: &str = "M.S\n.A.\nM.S";
+
+#[derive(Debug)]
+pub struct Grid {
+    data: grid::Grid<u8>,
+}
+
+/// A 2D pattern of bytes placed relative to an anchor cell. Cells not in the stencil match
+/// anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    cells: Vec<(Offset, u8)>,
+}
+
+impl Stencil {
+    /// The stencil matching `word` read from the anchor in `direction`.
+    pub fn word(word: &[u8], direction: Direction8) -> Self {
+        let step = direction.offset();
+        let cells = (0..)
+            .zip(word)
+            .map(|(idx, b)| (Offset::new(step.row * idx, step.col * idx), *b))
+            .collect();
+        Stencil { cells }
+    }
+
+    /// Builds a stencil from a picture of it, one row per line, anchored at the top-left
+    /// corner. `wildcard` marks the cells that match anything.
+    pub fn parse(pattern: &str, wildcard: char) -> Self {
+        let cells = (0..)
+            .zip(pattern.lines())
+            .flat_map(|(row, line)| {
+                (0..)
+                    .zip(line.bytes())
+                    .map(move |(col, b)| (Offset::new(row, col), b))
+            })
+            .filter(|(_, b)| char::from(*b) != wildcard)
+            .collect();
+        Stencil { cells }
+    }
+
+    /// The stencil rotated by 90° clockwise around its anchor.
+    pub fn rotate_right(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|(offset, b)| (Offset::new(offset.col, -offset.row), *b))
+            .collect();
+        Stencil { cells }
+    }
+
+    /// The distinct rotations of the stencil by multiples of 90°, starting with itself.
+    pub fn rotations(&self) -> Vec<Stencil> {
+        let mut rotations: Vec<Stencil> = Vec::with_capacity(4);
+        let mut stencil = self.clone();
+        for _ in 0..4 {
+            let next = stencil.rotate_right();
+            if !rotations.iter().any(|seen| seen.same_shape(&stencil)) {
+                rotations.push(stencil);
+            }
+            stencil = next;
+        }
+        rotations
+    }
+
+    /// Whether the stencils match the same cells, ignoring where they are anchored.
+    fn same_shape(&self, other: &Stencil) -> bool {
+        let normalized = |stencil: &Stencil| {
+            let min_row = stencil.cells.iter().map(|(o, _)| o.row).min().unwrap_or(0);
+            let min_col = stencil.cells.iter().map(|(o, _)| o.col).min().unwrap_or(0);
+            let mut cells = stencil
+                .cells
+                .iter()
+                .map(|(o, b)| (o.row - min_row, o.col - min_col, *b))
+                .collect::<Vec<_>>();
+            cells.sort_unstable();
+            cells
+        };
+        normalized(self) == normalized(other)
+    }
+}
+
+/// What to look for with [`Grid::find_words`].
+#[derive(Debug, Clone)]
+pub struct WordSearch {
+    words: Vec<Vec<u8>>,
+    directions: Vec<Direction8>,
+    wrap: bool,
+}
+
+impl WordSearch {
+    /// Searches for `words` in all eight directions without wrapping around the edges.
+    pub fn new<W: AsRef<[u8]>>(words: impl IntoIterator<Item = W>) -> Self {
+        WordSearch {
+            words: words.into_iter().map(|w| w.as_ref().to_vec()).collect(),
+            directions: Direction8::ALL.to_vec(),
+            wrap: false,
+        }
+    }
+
+    /// Only reads words in the given directions.
+    pub fn directions(mut self, directions: &[Direction8]) -> Self {
+        self.directions = directions.to_vec();
+        self
+    }
+
+    /// Lets words continue on the opposite edge of the grid.
+    pub fn wrapping(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+}
+
+/// An occurrence of `search.words[word]` in a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordMatch {
+    pub word: usize,
+    pub start: Coord,
+    pub direction: Direction8,
+}
+
+impl Grid {
+    fn find_x_mas_count(&self) -> usize {
+        Stencil::parse(X_MAS, '.')
+            .rotations()
+            .iter()
+            .map(|stencil| self.find_stencil(stencil, false).count())
+            .sum()
+    }
+
+    fn find_xmas_count(&self, needle: &[u8]) -> usize {
+        self.find_words(&WordSearch::new([needle])).len()
+    }
+
+    /// Finds every occurrence of the searched words, ordered by word, then direction, then
+    /// start position. Empty words never match.
+    pub fn find_words(&self, search: &WordSearch) -> Vec<WordMatch> {
+        let mut matches = Vec::new();
+        for (word_idx, word) in search.words.iter().enumerate() {
+            if word.is_empty() {
+                continue;
+            }
+            for &direction in &search.directions {
+                let stencil = Stencil::word(word, direction);
+                matches.extend(
+                    self.find_stencil(&stencil, search.wrap)
+                        .map(|start| WordMatch {
+                            word: word_idx,
+                            start,
+                            direction,
+                        }),
+                );
+            }
+        }
+        matches
+    }
+
+    /// Finds every anchor position at which `stencil` matches, row by row. With `wrap` the
+    /// stencil may continue on the opposite edge of the grid.
+    pub fn find_stencil<'a>(
+        &'a self,
+        stencil: &'a Stencil,
+        wrap: bool,
+    ) -> impl Iterator<Item = Coord> + 'a {
+        self.data
+            .iter()
+            .map(|(pos, _)| pos)
+            .filter(move |&anchor| self.stencil_matches_at(stencil, anchor, wrap))
+    }
+
+    /// Counts the occurrences of the searched words by extracting every row, column and
+    /// diagonal once and running a multi-pattern automaton over them. Gives the same count as
+    /// [`Grid::find_words`], which can be faster for large grids and many words, but doesn't
+    /// support wrapping.
+    pub fn count_words_multi_pattern(&self, search: &WordSearch) -> anyhow::Result<usize> {
+        ensure!(
+            !search.wrap,
+            "multi-pattern search doesn't support wrapping"
+        );
+        // An automaton would match empty words at every position, unlike `find_words`
+        let words = search.words.iter().filter(|word| !word.is_empty());
+        let forward = AhoCorasick::new(words.clone())?;
+        let reversed = words.map(|word| word.iter().rev().copied().collect::<Vec<_>>());
+        let backward = AhoCorasick::new(reversed)?;
+
+        // Each family of lines is read in its direction by `forward` and in the opposite one
+        // by `backward`
+        let data = &self.data;
+        let families = [
+            (Direction8::Right, to_lines(data.rows().map(<[u8]>::iter))),
+            (Direction8::Down, to_lines(data.columns())),
+            (Direction8::DownRight, to_lines(data.diagonals())),
+            (Direction8::DownLeft, to_lines(data.anti_diagonals())),
+        ];
+
+        let mut count = 0;
+        for (direction, lines) in &families {
+            for (automaton, direction) in [(&forward, *direction), (&backward, direction.reverse())]
+            {
+                if search.directions.contains(&direction) {
+                    count += lines
+                        .iter()
+                        .map(|line| automaton.find_overlapping_iter(line).count())
+                        .sum::<usize>();
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    fn stencil_matches_at(&self, stencil: &Stencil, anchor: Coord, wrap: bool) -> bool {
+        stencil.cells.iter().all(|&(offset, expected)| {
+            let pos = if wrap {
+                Some(self.data.offset_wrapping(anchor, offset))
+            } else {
+                self.data.offset(anchor, offset)
+            };
+            pos.is_some_and(|pos| self.data[pos] == expected)
+        })
+    }
+}
+
+fn to_lines<'a>(lines: impl Iterator<Item = impl Iterator<Item = &'a u8>>) -> Vec<Vec<u8>> {
+    lines.map(|line| line.copied().collect()).collect()
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data = grid::Grid::parse_with(s, |c| {
+            // Checked first, as chars up to U+00FF would convert to a byte as well
+            if c.is_ascii() {
+                Ok(c as u8)
+            } else {
+                Err("non-ASCII character")
+            }
+        })?;
+        Ok(Grid { data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_xmas_count() -> anyhow::Result<()> {
+        let s = r#"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX"#;
+        let grid = s.parse::<Grid>()?;
+
+        assert_eq!(grid.find_xmas_count(XMAS), 18);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_x_mas_count() -> anyhow::Result<()> {
+        let s = r#"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX"#;
+        let grid = s.parse::<Grid>()?;
+
+        assert_eq!(grid.find_x_mas_count(), 9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_words() -> anyhow::Result<()> {
+        let grid = "ABC\nDEF\nGHI".parse::<Grid>()?;
+        let search = WordSearch::new(["BC", "FI", "EA"]);
+
+        assert_eq!(
+            grid.find_words(&search),
+            vec![
+                WordMatch {
+                    word: 0,
+                    start: (0, 1),
+                    direction: Direction8::Right
+                },
+                WordMatch {
+                    word: 1,
+                    start: (1, 2),
+                    direction: Direction8::Down
+                },
+                WordMatch {
+                    word: 2,
+                    start: (1, 1),
+                    direction: Direction8::UpLeft
+                },
+            ]
+        );
+
+        let search = search.directions(&[Direction8::Right, Direction8::Left]);
+        assert_eq!(grid.find_words(&search).len(), 1);
+
+        let search = WordSearch::new(["CA", "IC"]).wrapping(true);
+        let starts = grid
+            .find_words(&search)
+            .iter()
+            .map(|m| (m.start, m.direction))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            vec![((0, 2), Direction8::Right), ((2, 2), Direction8::Down)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_pattern_matches_find_words() -> anyhow::Result<()> {
+        // A pseudo-random grid, so the words overlap in every direction
+        let mut state = 7_u32;
+        let text = (0..12)
+            .map(|_| {
+                (0..15)
+                    .map(|_| {
+                        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                        char::from(b"XMAS"[(state >> 16) as usize % 4])
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let grid = text.parse::<Grid>()?;
+
+        let searches = [
+            WordSearch::new([XMAS]),
+            WordSearch::new(["XMAS", "MAS", "SAM", "XX", "A"]),
+            WordSearch::new(["MAS", "AS"]).directions(&[
+                Direction8::Up,
+                Direction8::DownRight,
+                Direction8::Left,
+            ]),
+        ];
+        for search in &searches {
+            assert_eq!(
+                grid.count_words_multi_pattern(search)?,
+                grid.find_words(search).len()
+            );
+        }
+        assert!(grid
+            .count_words_multi_pattern(&WordSearch::new([XMAS]).wrapping(true))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_words_never_match() -> anyhow::Result<()> {
+        let grid = "XMAS\nSAMX\nMASX".parse::<Grid>()?;
+
+        for search in [
+            WordSearch::new([""]),
+            WordSearch::new(["", "XMAS", "", "AS"]),
+        ] {
+            let count = grid.count_words_multi_pattern(&search)?;
+            assert_eq!(count, grid.find_words(&search).len());
+        }
+        assert!(grid.find_words(&WordSearch::new([""])).is_empty());
+        assert_eq!(
+            grid.count_words_multi_pattern(&WordSearch::new(["", "XMAS"]))?,
+            grid.count_words_multi_pattern(&WordSearch::new(["XMAS"]))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_rejects_non_ascii() {
+        let err = "XM\nXé".parse::<Grid>().unwrap_err();
+
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(err.message.contains("non-ASCII character"));
+    }
+
+    #[test]
+    fn test_stencil_rotations() {
+        let x_mas = Stencil::parse(X_MAS, '.');
+        assert_eq!(x_mas.rotations().len(), 4);
+
+        let plus = Stencil::parse(".A.\nAAA\n.A.", '.');
+        assert_eq!(plus.rotations(), vec![plus]);
+    }
+}
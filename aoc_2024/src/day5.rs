@@ -0,0 +1,557 @@
+use anyhow::{anyhow, bail};
+use common::bitset::{BitMatrix, BitSet};
+use common::graph::DiGraph;
+use common::intern::Interner;
+use common::parse::ParseError;
+use common::parser::{
+    from_str, lines_of, literal, padded, parse_all, section_pair, separated, separated_pair,
+    take_while1,
+};
+use common::Solution;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = PrintInput;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(PrintInput::from_str(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+        input.sum_of_valid_update_middle_page()
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+        input.sum_of_invalid_update_middle_page()
+    }
+}
+
+/// What can identify a page: integers of any width, string labels, ...
+pub trait Page: Clone + Eq + Hash + Display {}
+
+impl<P: Clone + Eq + Hash + Display> Page for P {}
+
+impl<P> FromStr for PrintInput<P>
+where
+    P: Page + FromStr,
+    P::Err: Display,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // A page is anything up to the next separator, so labels aren't limited to numbers
+        let page = |separator: char| {
+            from_str::<P>(take_while1(move |c: char| {
+                c != separator && !c.is_whitespace()
+            }))
+        };
+        let rule = separated_pair(page('|'), padded(literal("|")), page('|'));
+        let update = separated(page(','), padded(literal(",")));
+        let (rules, updates) = parse_all(s, section_pair(lines_of(rule), lines_of(update)))?;
+
+        let mut pages = Interner::default();
+        let mut ordering_rules: HashMap<usize, HashSet<usize>> = HashMap::default();
+        for (p1, p2) in rules {
+            let (p1, p2) = (pages.intern(p1), pages.intern(p2));
+            ordering_rules.entry(p1).or_default().insert(p2);
+        }
+        let page_update_seq = updates
+            .into_iter()
+            .map(|update| update.into_iter().map(|page| pages.intern(page)).collect())
+            .collect();
+        let mut rule_matrix = BitMatrix::new(pages.len());
+        for (&page, subsequent_pages) in &ordering_rules {
+            for &subsequent_page in subsequent_pages {
+                rule_matrix.set(page, subsequent_page);
+            }
+        }
+        Ok(PrintInput {
+            pages,
+            ordering_rules,
+            rule_matrix,
+            rule_lookup: RuleLookup::default(),
+            page_update_seq,
+        })
+    }
+}
+
+/// How [`PrintInput`] checks whether an update is valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuleLookup {
+    /// Looks up the pages that must come after each page in a hash set.
+    #[default]
+    HashSet,
+    /// Tracks the pages seen so far in a bitset and checks them against a row of the rule
+    /// adjacency matrix, which is linear in the length of the update.
+    Bitset,
+}
+
+/// The ordering rules and page updates, with pages interned into dense ids.
+#[derive(Debug)]
+pub struct PrintInput<P = u32> {
+    pages: Interner<P>,
+    // page -> set of pages that should come after the said page
+    ordering_rules: HashMap<usize, HashSet<usize>>,
+    // the same rules as an adjacency matrix, where `rule_matrix.get(x, y)` means `x|y`
+    rule_matrix: BitMatrix,
+    rule_lookup: RuleLookup,
+    // the sequence of page update (list of pages) to check
+    page_update_seq: Vec<Vec<usize>>,
+}
+
+/// A rule `before|after` broken by an update that has `after` ahead of `before`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleViolation<P> {
+    pub before: P,
+    pub after: P,
+    pub before_idx: usize,
+    pub after_idx: usize,
+}
+
+impl<P: Display> Display for RuleViolation<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule {}|{} is broken: page {} at index {} comes before page {} at index {}",
+            self.before, self.after, self.after, self.after_idx, self.before, self.before_idx
+        )
+    }
+}
+
+/// An update along with every rule it breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidUpdate<P> {
+    /// Zero-based position of the update in the input.
+    pub index: usize,
+    pub pages: Vec<P>,
+    pub violations: Vec<RuleViolation<P>>,
+}
+
+impl<P: Display> Display for InvalidUpdate<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "update {} ({}) breaks {} rule(s):",
+            self.index + 1,
+            self.pages.iter().join(","),
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            writeln!(f, "  - {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: Page> PrintInput<P> {
+    /// Selects how the validity of updates is checked.
+    pub fn with_rule_lookup(mut self, rule_lookup: RuleLookup) -> Self {
+        self.rule_lookup = rule_lookup;
+        self
+    }
+
+    /// Every rule broken by `update`, ordered by the position of the misplaced later page.
+    pub fn rule_violations(&self, update: &[P]) -> Vec<RuleViolation<P>> {
+        self.rule_violations_of_ids(&self.ids_of(update))
+            .into_iter()
+            .map(|(after_idx, before_idx)| RuleViolation {
+                before: update[before_idx].clone(),
+                after: update[after_idx].clone(),
+                before_idx,
+                after_idx,
+            })
+            .collect()
+    }
+
+    /// The updates breaking at least one rule, in input order.
+    pub fn invalid_updates(&self) -> Vec<InvalidUpdate<P>> {
+        self.first_violations()
+            .enumerate()
+            .filter(|(_, (_, violation))| violation.is_some())
+            .map(|(index, (update, _))| {
+                let pages = self.resolve(update);
+                let violations = self.rule_violations(&pages);
+                InvalidUpdate {
+                    index,
+                    pages,
+                    violations,
+                }
+            })
+            .collect()
+    }
+
+    /// Reorders the pages of `update` so that it satisfies every rule between its pages, failing
+    /// if those rules contradict each other.
+    pub fn fix_incorrectly_ordered_update(&self, update: &[P]) -> anyhow::Result<Vec<P>> {
+        match self.update_rule_graph(&self.ids_of(update)).toposort() {
+            Ok(order) => Ok(order.into_iter().map(|idx| update[idx].clone()).collect()),
+            Err(cycle) => bail!(
+                "contradictory ordering rules between pages {}",
+                cycle.nodes.iter().map(|&idx| &update[idx]).join(" -> ")
+            ),
+        }
+    }
+
+    /// Maps `update` to page ids. Pages that don't appear in the input get ids past the
+    /// interned ones, which no rule mentions.
+    fn ids_of(&self, update: &[P]) -> Vec<usize> {
+        let mut unknown = self.pages.len()..;
+        update
+            .iter()
+            .map(|page| {
+                self.pages
+                    .get(page)
+                    .unwrap_or_else(|| unknown.next().expect("ids never run out"))
+            })
+            .collect()
+    }
+
+    fn resolve(&self, update: &[usize]) -> Vec<P> {
+        update
+            .iter()
+            .map(|&id| self.pages.resolve(id).clone())
+            .collect()
+    }
+
+    /// The `(after_idx, before_idx)` index pairs of every rule broken by `update`.
+    fn rule_violations_of_ids(&self, update: &[usize]) -> Vec<(usize, usize)> {
+        let mut violations = Vec::new();
+        for (after_idx, after) in update.iter().enumerate() {
+            for (before_idx, before) in update.iter().enumerate().skip(after_idx + 1) {
+                let breaks_rule = self
+                    .ordering_rules
+                    .get(before)
+                    .is_some_and(|subsequent_pages| subsequent_pages.contains(after));
+                if breaks_rule {
+                    violations.push((after_idx, before_idx));
+                }
+            }
+        }
+        violations
+    }
+
+    /// The subgraph of the ordering rules induced by the pages of `update`, with nodes being
+    /// indices into `update`.
+    fn update_rule_graph(&self, update: &[usize]) -> DiGraph {
+        let mut graph = DiGraph::new(update.len());
+        for (from, page) in update.iter().enumerate() {
+            let Some(subsequent_pages) = self.ordering_rules.get(page) else {
+                continue;
+            };
+            for (to, other) in update.iter().enumerate() {
+                if subsequent_pages.contains(other) {
+                    graph.add_edge(from, to);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Pairs every update with the indices of a pair of its pages breaking a rule, the first
+    /// of which must come before the second, or `None` if the update is valid.
+    fn first_violations(&self) -> impl Iterator<Item = (&[usize], Option<(usize, usize)>)> {
+        // Shared by all the updates so that checking them doesn't allocate
+        let mut seen = BitSet::new(self.rule_matrix.len());
+        self.page_update_seq.iter().map(move |update| {
+            let violation = match self.rule_lookup {
+                RuleLookup::HashSet => self.first_violation_hashed(update),
+                RuleLookup::Bitset => self.first_violation_bitset(update, &mut seen),
+            };
+            (update.as_slice(), violation)
+        })
+    }
+
+    fn first_violation_bitset(
+        &self,
+        update: &[usize],
+        seen: &mut BitSet,
+    ) -> Option<(usize, usize)> {
+        seen.clear();
+        for (idx, &page) in update.iter().enumerate() {
+            // The update is invalid as soon as a page has to come before one already seen
+            if self.rule_matrix.row_intersects(page, seen) {
+                let earlier_idx = update[..idx]
+                    .iter()
+                    .position(|&earlier| self.rule_matrix.get(page, earlier))
+                    .expect("violation must have an earlier page");
+                return Some((idx, earlier_idx));
+            }
+            if page < self.rule_matrix.len() {
+                seen.insert(page);
+            }
+        }
+        None
+    }
+
+    fn first_violation_hashed(&self, update: &[usize]) -> Option<(usize, usize)> {
+        let fallback_set = HashSet::default();
+        let update_len = update.len();
+        // Start iterating the "update" list in reverse order. For each item, figure out
+        // whether any of the subsequent pages are part of the set of pages which should have come
+        // *after* the given page. If yes, we know the page update list is invalid.
+        for (desc_idx, page) in update.iter().rev().enumerate() {
+            let subsequent_pages = self.ordering_rules.get(page).unwrap_or(&fallback_set);
+            for (asc_idx, cur_page) in update[..(update_len - desc_idx - 1)].iter().enumerate() {
+                if subsequent_pages.contains(cur_page) {
+                    return Some((update_len - desc_idx - 1, asc_idx));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<P: Page + Copy + TryInto<i128>> PrintInput<P> {
+    pub fn sum_of_valid_update_middle_page(&self) -> anyhow::Result<i128> {
+        sum_pages(
+            self.first_violations()
+                .filter(|(_, violation)| violation.is_none())
+                .map(|(update, _)| *self.pages.resolve(update[update.len() / 2])),
+        )
+    }
+
+    pub fn sum_of_invalid_update_middle_page(&self) -> anyhow::Result<i128> {
+        let mut middle_pages = Vec::new();
+        for (update, violation) in self.first_violations() {
+            if violation.is_some() {
+                let fixed_update = self.fix_incorrectly_ordered_update(&self.resolve(update))?;
+                middle_pages.push(fixed_update[fixed_update.len() / 2]);
+            }
+        }
+        sum_pages(middle_pages)
+    }
+}
+
+/// Adds up `pages` in an `i128`, so narrow page types don't overflow, failing if a page doesn't
+/// fit in one or the sum overflows it.
+fn sum_pages<P: Page + Copy + TryInto<i128>>(
+    pages: impl IntoIterator<Item = P>,
+) -> anyhow::Result<i128> {
+    pages.into_iter().try_fold(0_i128, |sum, page| {
+        let value = page
+            .try_into()
+            .map_err(|_| anyhow!("page {page} doesn't fit in an i128"))?;
+        sum.checked_add(value)
+            .ok_or_else(|| anyhow!("the sum of the middle pages overflows an i128"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47"#;
+
+    #[test]
+    fn test_sum_of_valid_update_middle_page() -> anyhow::Result<()> {
+        let print_input = INPUT.parse::<PrintInput>()?;
+
+        assert_eq!(143, print_input.sum_of_valid_update_middle_page()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_tolerates_messy_input() -> anyhow::Result<()> {
+        let messy_input = format!("{}\r\n", INPUT.replace('\n', "\r\n").replace(",", ", "))
+            .replacen("\r\n\r\n", "\n \n\n", 1);
+        let print_input = messy_input.parse::<PrintInput>()?;
+
+        assert_eq!(143, print_input.sum_of_valid_update_middle_page()?);
+        assert_eq!(123, print_input.sum_of_invalid_update_middle_page()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_reports_error_location() {
+        let err = "47|53\n97-13\n\n75,47".parse::<PrintInput>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(err.message.starts_with("invalid value `97-13`"));
+
+        let err = "47|53\n\n75,4x0,61".parse::<PrintInput>().unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.snippet, "75,4x0,61");
+    }
+
+    #[test]
+    fn test_sum_of_invalid_update_middle_page() -> anyhow::Result<()> {
+        let print_input = INPUT.parse::<PrintInput>()?;
+
+        assert_eq!(123, print_input.sum_of_invalid_update_middle_page()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule_lookups_agree() -> anyhow::Result<()> {
+        for rule_lookup in [RuleLookup::HashSet, RuleLookup::Bitset] {
+            let print_input = INPUT.parse::<PrintInput>()?.with_rule_lookup(rule_lookup);
+
+            assert_eq!(143, print_input.sum_of_valid_update_middle_page()?);
+            assert_eq!(123, print_input.sum_of_invalid_update_middle_page()?);
+            for (update, violation) in print_input.first_violations() {
+                assert_eq!(
+                    violation.is_some(),
+                    !print_input.rule_violations_of_ids(update).is_empty()
+                );
+                if let Some((before_idx, after_idx)) = violation {
+                    assert!(print_input.ordering_rules[&update[before_idx]]
+                        .contains(&update[after_idx]));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fix_incorrectly_ordered_update() -> anyhow::Result<()> {
+        let print_input = INPUT.parse::<PrintInput>()?;
+
+        assert_eq!(
+            print_input.fix_incorrectly_ordered_update(&[97, 13, 75, 29, 47])?,
+            vec![97, 75, 47, 29, 13]
+        );
+
+        let contradictory = "1|2\n2|3\n3|1\n\n4,1,2,3".parse::<PrintInput>()?;
+        let err = contradictory
+            .fix_incorrectly_ordered_update(&[4, 1, 2, 3])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "contradictory ordering rules between pages 1 -> 2 -> 3"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule_violations() -> anyhow::Result<()> {
+        let print_input = INPUT.parse::<PrintInput>()?;
+
+        let violations = print_input.rule_violations(&[97, 13, 75, 29, 47]);
+        let rules = violations
+            .iter()
+            .map(|v| (v.before, v.after, v.before_idx, v.after_idx))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                (75, 13, 2, 1),
+                (29, 13, 3, 1),
+                (47, 13, 4, 1),
+                (47, 29, 4, 3)
+            ]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "rule 75|13 is broken: page 13 at index 1 comes before page 75 at index 2"
+        );
+
+        let invalid_updates = print_input.invalid_updates();
+        assert_eq!(
+            invalid_updates.iter().map(|u| u.index).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+        assert!(invalid_updates[0]
+            .to_string()
+            .starts_with("update 4 (75,97,47,61,53) breaks 1 rule(s):\n  - rule 97|75"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pages_wider_than_a_byte() -> anyhow::Result<()> {
+        let print_input = "1000|300\n300|70000\n\n300,1000,70000".parse::<PrintInput>()?;
+
+        assert_eq!(print_input.sum_of_valid_update_middle_page()?, 0);
+        assert_eq!(print_input.sum_of_invalid_update_middle_page()?, 300);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_page_types() -> anyhow::Result<()> {
+        const INPUT: &str = "1000|300\n300|70000\n\n300,1000,70000\n1000,300,70000";
+
+        let print_input = INPUT.parse::<PrintInput<usize>>()?;
+        assert_eq!(print_input.sum_of_valid_update_middle_page()?, 300);
+        assert_eq!(print_input.sum_of_invalid_update_middle_page()?, 300);
+
+        let print_input = INPUT.parse::<PrintInput<i64>>()?;
+        assert_eq!(print_input.sum_of_valid_update_middle_page()?, 300);
+        assert_eq!(print_input.sum_of_invalid_update_middle_page()?, 300);
+
+        let huge = u128::MAX / 2;
+        let print_input =
+            format!("1|{huge}\n\n1,{huge},2\n{huge},1").parse::<PrintInput<u128>>()?;
+        let huge = i128::try_from(huge)?;
+        assert_eq!(print_input.sum_of_valid_update_middle_page()?, huge);
+        assert_eq!(print_input.sum_of_invalid_update_middle_page()?, huge);
+
+        let print_input =
+            "100|200\n100|150\n\n1,200,2\n1,100,2\n200,100\n150,100".parse::<PrintInput<u8>>()?;
+        assert_eq!(print_input.sum_of_valid_update_middle_page()?, 300);
+        assert_eq!(print_input.sum_of_invalid_update_middle_page()?, 350);
+
+        let print_input = format!("1|2\n\n1,{},2", u128::MAX).parse::<PrintInput<u128>>()?;
+        assert!(print_input.sum_of_valid_update_middle_page().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_labels() -> anyhow::Result<()> {
+        let print_input = "core|net\nnet|app\ncore|app\n\napp, net, core\ncore,app"
+            .parse::<PrintInput<String>>()?;
+        let labels = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            print_input.fix_incorrectly_ordered_update(&labels(&["app", "net", "core"]))?,
+            labels(&["core", "net", "app"])
+        );
+        let invalid_updates = print_input.invalid_updates();
+        assert_eq!(invalid_updates.len(), 1);
+        assert_eq!(invalid_updates[0].violations.len(), 3);
+        assert_eq!(
+            invalid_updates[0].violations[0].to_string(),
+            "rule net|app is broken: page app at index 0 comes before page net at index 1"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,618 @@
+use anyhow::bail;
+use common::cycle::{find_cycle_hashed, state_after};
+use common::geom::{Direction4, Offset, Point};
+use common::grid::Grid;
+use common::parse::ParseError;
+use common::Solution;
+use fxhash::FxHashSet;
+use itertools::Either;
+use std::cmp::PartialEq;
+use std::fmt::{self, Display};
+use std::iter;
+#[cfg(feature = "parallel")]
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::thread;
+
+pub mod render;
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = LabInput;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(LabInput::from_str(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+        Ok(input.patrol_position_path().len())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+        let guard_path_positions = input.patrol_position_path();
+        Ok(obstruction_position_count(input, &guard_path_positions))
+    }
+}
+
+pub type Position = Point;
+
+pub type GuardDirection = Direction4;
+
+/// A position where the guard turns, along with the direction it faces after turning.
+pub type Corner = (Position, GuardDirection);
+
+/// An obstruction that traps the guard, along with the loop of corners the guard then keeps
+/// walking, starting from the first one it reaches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopingObstruction {
+    pub position: Position,
+    pub cycle: Vec<Corner>,
+}
+
+impl Display for LoopingObstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Point { row, col } = self.position;
+        write!(f, "obstruction at ({row}, {col})")?;
+        if f.alternate() {
+            write!(f, " loops through {} corners:", self.cycle.len())?;
+            for (Point { row, col }, direction) in &self.cycle {
+                write!(f, " ({row}, {col}) {direction:?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A guard's starting position and the direction it initially faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guard {
+    pub position: Position,
+    pub direction: GuardDirection,
+}
+
+impl Guard {
+    fn corner(self) -> Corner {
+        (self.position, self.direction)
+    }
+}
+
+/// Where a guard goes when it patrols the map on its own.
+#[derive(Debug, Clone)]
+pub struct GuardReport {
+    pub guard: Guard,
+    /// Every position the guard stands on, including its starting position.
+    pub visited: FxHashSet<Position>,
+    /// Whether the guard gets stuck in a loop rather than leaving the map.
+    pub is_loop: bool,
+}
+
+impl Display for GuardReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Guard {
+            position: Point { row, col },
+            direction,
+        } = self.guard;
+        let outcome = if self.is_loop {
+            "gets stuck in a loop"
+        } else {
+            "leaves the map"
+        };
+        write!(
+            f,
+            "guard at ({row}, {col}) facing {direction:?} visits {} position(s) and {outcome}",
+            self.visited.len()
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LabInput {
+    grid: Grid<MapTileType>,
+    /// Every guard on the map in reading order. The puzzle's guard is the first one.
+    guards: Vec<Guard>,
+    jump_table: JumpTable,
+}
+
+/// For every free cell and direction, where the guard stops when walking from that cell until
+/// the next obstruction, so a whole straight stretch of the patrol is a single lookup.
+#[derive(Debug, Clone)]
+struct JumpTable {
+    /// Indexed by `GuardDirection as usize`; `None` when the guard walks off the map instead.
+    stops: Grid<[Option<Position>; 4]>,
+}
+
+impl JumpTable {
+    fn new(grid: &Grid<MapTileType>) -> Self {
+        let mut stops = Grid::filled(grid.width(), grid.height(), [None; 4]);
+        let positions = grid.iter().map(|(pos, _)| pos).collect::<Vec<_>>();
+        for direction in GuardDirection::ALL {
+            // Fill in the cell ahead before the cells behind it
+            let in_order = match direction {
+                GuardDirection::Up | GuardDirection::Left => Either::Left(positions.iter()),
+                GuardDirection::Down | GuardDirection::Right => {
+                    Either::Right(positions.iter().rev())
+                }
+            };
+            for &pos in in_order {
+                stops[pos][direction as usize] = match grid.offset(pos, direction.offset()) {
+                    None => None,
+                    Some(next) if grid[next] == MapTileType::Obstructed => Some(pos.into()),
+                    Some(next) => stops[next][direction as usize],
+                };
+            }
+        }
+        JumpTable { stops }
+    }
+
+    /// Where the guard stops walking from `pos` in `direction`, also taking
+    /// `extra_obstruction` into account, or `None` if the guard leaves the map.
+    fn stop(
+        &self,
+        pos: Position,
+        direction: GuardDirection,
+        extra_obstruction: Option<Position>,
+    ) -> Option<Position> {
+        let stop = self.stops[pos][direction as usize];
+        let Some(obstruction) = extra_obstruction else {
+            return stop;
+        };
+        // The extra obstruction only matters if the guard would reach it
+        let distance = pos.manhattan(&obstruction);
+        let step = direction.offset();
+        let scaled =
+            |steps: usize| Offset::new(step.row * steps as isize, step.col * steps as isize);
+        let is_ahead = distance > 0 && pos.checked_add(scaled(distance)) == Some(obstruction);
+        let is_reached = stop.is_none_or(|stop| distance <= pos.manhattan(&stop));
+        if is_ahead && is_reached {
+            pos.checked_add(scaled(distance - 1))
+        } else {
+            stop
+        }
+    }
+}
+
+impl FromStr for LabInput {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grid = Grid::parse_with(s, MapTileType::try_from)?;
+        let guards = grid
+            .iter()
+            .filter_map(|(pos, tile)| match tile {
+                MapTileType::Guard(direction) => Some(Guard {
+                    position: pos.into(),
+                    direction: *direction,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if guards.is_empty() {
+            return Err(ParseError::at_offset(
+                s,
+                s.len(),
+                "failed to find guard position",
+            ));
+        }
+        let jump_table = JumpTable::new(&grid);
+        Ok(LabInput {
+            grid,
+            guards,
+            jump_table,
+        })
+    }
+}
+
+impl TryFrom<char> for MapTileType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        let tile = match value {
+            '.' => MapTileType::Empty,
+            '#' => MapTileType::Obstructed,
+            '^' => MapTileType::Guard(GuardDirection::Up),
+            '>' => MapTileType::Guard(GuardDirection::Right),
+            'v' => MapTileType::Guard(GuardDirection::Down),
+            '<' => MapTileType::Guard(GuardDirection::Left),
+            _ => bail!("invalid map tile: {}", value),
+        };
+        Ok(tile)
+    }
+}
+
+impl LabInput {
+    /// Every guard on the map, in reading order.
+    pub fn guards(&self) -> &[Guard] {
+        &self.guards
+    }
+
+    /// The guard the puzzle is about: the first one in reading order.
+    pub fn guard(&self) -> Guard {
+        self.guards[0]
+    }
+
+    /// Simulates every guard on its own, ignoring the others, in reading order.
+    pub fn guard_reports(&self) -> Vec<GuardReport> {
+        self.guards
+            .iter()
+            .map(|&guard| {
+                let (mut visited, is_loop) = self.patrol(guard);
+                visited.insert(guard.position);
+                GuardReport {
+                    guard,
+                    visited,
+                    is_loop,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the guard ends up walking in circles once `extra_obstruction` is added to the map.
+    /// The obstruction is only virtual, so the map is never modified.
+    fn is_guard_stuck_in_loop(&self, extra_obstruction: Option<Position>) -> bool {
+        // Only the corners need to be tracked since the guard can only be stuck in a loop if it
+        // turns at the same position facing the same direction more than once
+        find_cycle_hashed(self.guard().corner(), |corner| {
+            self.next_corner(*corner, extra_obstruction)
+        })
+        .is_some()
+    }
+
+    /// Every position where a new obstruction would trap the guard in a loop, ordered by
+    /// position, along with the loop the guard ends up walking.
+    pub fn looping_obstructions(&self) -> Vec<LoopingObstruction> {
+        let mut candidates = self
+            .patrol_position_path()
+            .into_iter()
+            .filter(|pos| *pos != self.guard().position)
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        search_candidates(&candidates, search_threads(), |position| {
+            let cycle = self.guard_loop(Some(position))?;
+            Some(LoopingObstruction { position, cycle })
+        })
+    }
+
+    /// The corners the guard keeps turning at once `extra_obstruction` is added to the map, or
+    /// `None` if the guard leaves the map instead.
+    fn guard_loop(&self, extra_obstruction: Option<Position>) -> Option<Vec<Corner>> {
+        let initial = self.guard().corner();
+        let step = |corner: &Corner| self.next_corner(*corner, extra_obstruction);
+        let cycle = find_cycle_hashed(initial, step)?;
+        let first = state_after(initial, step, cycle.start)?;
+        Some(
+            iter::successors(Some(first), step)
+                .take(cycle.length)
+                .collect(),
+        )
+    }
+
+    /// Moves the guard from `pos` in `guard_direction` until it hits an obstruction, returning
+    /// the position and the new direction after turning, or `None` if the guard leaves the map.
+    fn next_corner(
+        &self,
+        (pos, guard_direction): (Position, GuardDirection),
+        extra_obstruction: Option<Position>,
+    ) -> Option<(Position, GuardDirection)> {
+        let stop = self
+            .jump_table
+            .stop(pos, guard_direction, extra_obstruction)?;
+        Some((stop, guard_direction.turn_right()))
+    }
+
+    fn patrol_position_path(&self) -> FxHashSet<Position> {
+        self.patrol(self.guard()).0
+    }
+
+    /// Walks `guard` until it leaves the map or gets stuck in a loop, returning every position
+    /// it steps onto and whether it got stuck.
+    fn patrol(&self, guard: Guard) -> (FxHashSet<Position>, bool) {
+        let mut visited_positions = FxHashSet::default();
+        // As in `is_guard_stuck_in_loop`, revisiting a corner is enough to detect a loop
+        let mut corners = FxHashSet::default();
+        let (mut pos, mut guard_direction) = guard.corner();
+
+        while let Some((new_pos, new_dir)) = next_move(pos, guard_direction, &self.grid) {
+            // It's worth noting that the obstruction detection mechanism can't be hardcoded in the
+            // next_move function which will result in a subtle bug. This is because that next_move
+            // function would fail to handle the corner case wherein changing the direction would be
+            // immediately met by a new obstruction. By having the obstruction detection logic in this
+            // loop we ensure that the corner case would be easily handled. For example in the day6.txt
+            // if you are standing at (21, 16) facing LEFT, then the next_move with obstruction logic
+            // included would put us at (20, 16) which would be wrong given that this is also an
+            // obstruction. Using this check inside the loop ensures such cases work as expected.
+            if self.grid[new_pos] == MapTileType::Obstructed {
+                guard_direction = new_dir.turn_right();
+                if !corners.insert((pos, guard_direction)) {
+                    return (visited_positions, true);
+                }
+                continue;
+            }
+            visited_positions.insert(new_pos);
+            pos = new_pos;
+            guard_direction = new_dir;
+        }
+
+        (visited_positions, false)
+    }
+}
+
+fn next_move(
+    position: Position,
+    direction: GuardDirection,
+    grid: &Grid<MapTileType>,
+) -> Option<(Position, GuardDirection)> {
+    let new_position = grid.offset(position, direction.offset())?;
+    Some((new_position.into(), direction))
+}
+
+fn obstruction_position_count(
+    lab_input: &LabInput,
+    guard_path_positions: &FxHashSet<Position>,
+) -> usize {
+    // Only positions on the original path can change where the guard goes
+    let candidates = guard_path_positions
+        .iter()
+        .copied()
+        .filter(|pos| *pos != lab_input.guard().position)
+        .collect::<Vec<_>>();
+    count_loop_obstructions(lab_input, &candidates, search_threads())
+}
+
+/// Counts the candidate obstructions that trap the guard in a loop, split across `threads`.
+fn count_loop_obstructions(lab_input: &LabInput, candidates: &[Position], threads: usize) -> usize {
+    search_candidates(candidates, threads, |pos| {
+        lab_input.is_guard_stuck_in_loop(Some(pos)).then_some(())
+    })
+    .len()
+}
+
+/// Runs `search` on every candidate obstruction, split across `threads`, keeping the results
+/// in candidate order.
+fn search_candidates<T, F>(candidates: &[Position], threads: usize, search: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(Position) -> Option<T> + Sync,
+{
+    let search_chunk = |chunk: &[Position]| {
+        chunk
+            .iter()
+            .filter_map(|pos| search(*pos))
+            .collect::<Vec<_>>()
+    };
+    if threads <= 1 {
+        return search_chunk(candidates);
+    }
+    let chunk_size = candidates.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let handles = candidates
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| search_chunk(chunk)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("obstruction search thread panicked"))
+            .collect()
+    })
+}
+
+#[cfg(feature = "parallel")]
+fn search_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn search_threads() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MapTileType {
+    Obstructed,
+    Empty,
+    Guard(GuardDirection),
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    pub(super) const INPUT_1: &str = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#;
+
+    // To handle the corner case of obstruction followed by an obstruction
+    pub(super) const INPUT_2: &str = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+........#.
+.#..^.....
+........#.
+#.........
+......#..."#;
+
+    #[test]
+    fn test_main_1() -> anyhow::Result<()> {
+        let lab_input = LabInput::from_str(INPUT_1)?;
+        let guard_path_positions = lab_input.patrol_position_path();
+
+        assert_eq!(guard_path_positions.len(), 41);
+        assert_eq!(
+            obstruction_position_count(&lab_input, &guard_path_positions),
+            6
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_main_2() -> anyhow::Result<()> {
+        let lab_input = LabInput::from_str(INPUT_2)?;
+        let guard_path_positions = lab_input.patrol_position_path();
+
+        assert_eq!(guard_path_positions.len(), 13);
+        assert_eq!(
+            obstruction_position_count(&lab_input, &guard_path_positions),
+            1
+        );
+        Ok(())
+    }
+
+    /// Walks the guard cell by cell, to check the jump table against.
+    fn walk_to_next_corner(
+        lab_input: &LabInput,
+        (mut pos, direction): (Position, GuardDirection),
+        extra_obstruction: Option<Position>,
+    ) -> Option<(Position, GuardDirection)> {
+        loop {
+            let (new_pos, _) = next_move(pos, direction, &lab_input.grid)?;
+            if extra_obstruction == Some(new_pos)
+                || lab_input.grid[new_pos] == MapTileType::Obstructed
+            {
+                return Some((pos, direction.turn_right()));
+            }
+            pos = new_pos;
+        }
+    }
+
+    #[test]
+    fn test_jump_table_matches_walking() -> anyhow::Result<()> {
+        let lab_input = LabInput::from_str(INPUT_2)?;
+        let free = lab_input
+            .grid
+            .iter()
+            .filter(|(_, tile)| **tile != MapTileType::Obstructed)
+            .map(|(pos, _)| Position::from(pos))
+            .collect::<Vec<_>>();
+
+        for &pos in &free {
+            for direction in GuardDirection::ALL {
+                let corner = (pos, direction);
+                for extra_obstruction in
+                    [None, Some(Position::new(6, 8)), Some(Position::new(0, 4))]
+                        .into_iter()
+                        .chain(free.iter().map(|pos| Some(*pos)).step_by(7))
+                {
+                    if extra_obstruction == Some(pos) {
+                        continue;
+                    }
+                    assert_eq!(
+                        lab_input.next_corner(corner, extra_obstruction),
+                        walk_to_next_corner(&lab_input, corner, extra_obstruction),
+                        "{corner:?} with {extra_obstruction:?}"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_looping_obstructions() -> anyhow::Result<()> {
+        let lab_input = LabInput::from_str(INPUT_1)?;
+        let looping_obstructions = lab_input.looping_obstructions();
+
+        let positions = looping_obstructions
+            .iter()
+            .map(|obstruction| obstruction.position.into())
+            .collect::<Vec<(usize, usize)>>();
+        assert_eq!(
+            positions,
+            vec![(6, 3), (7, 6), (7, 7), (8, 1), (8, 3), (9, 7)]
+        );
+        for LoopingObstruction { position, cycle } in &looping_obstructions {
+            for (idx, corner) in cycle.iter().enumerate() {
+                let next = cycle[(idx + 1) % cycle.len()];
+                assert_eq!(lab_input.next_corner(*corner, Some(*position)), Some(next));
+            }
+        }
+
+        let first = &looping_obstructions[0];
+        assert_eq!(first.to_string(), "obstruction at (6, 3)");
+        assert_eq!(
+            format!("{first:#}"),
+            "obstruction at (6, 3) loops through 4 corners: (6, 4) Up (1, 4) Right (1, 8) Down (6, 8) Left"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_guards() -> anyhow::Result<()> {
+        let lab_input = LabInput::from_str(
+            r#".#..>
+....#
+.^...
+#..v.
+.<.#."#,
+        )?;
+
+        let guards = lab_input
+            .guards()
+            .iter()
+            .map(|guard| (guard.position.into(), guard.direction))
+            .collect::<Vec<((usize, usize), _)>>();
+        assert_eq!(
+            guards,
+            vec![
+                ((0, 4), GuardDirection::Right),
+                ((2, 1), GuardDirection::Up),
+                ((3, 3), GuardDirection::Down),
+                ((4, 1), GuardDirection::Left),
+            ]
+        );
+        assert_eq!(lab_input.guard(), lab_input.guards()[0]);
+
+        let reports = lab_input.guard_reports();
+        let outcomes = reports
+            .iter()
+            .map(|report| (report.visited.len(), report.is_loop))
+            .collect::<Vec<_>>();
+        assert_eq!(outcomes, vec![(1, false), (8, true), (8, true), (2, false)]);
+        // Both looping guards walk the same loop, just starting from different positions
+        assert_eq!(reports[1].visited, reports[2].visited);
+        assert_eq!(
+            reports[1].to_string(),
+            "guard at (2, 1) facing Up visits 8 position(s) and gets stuck in a loop"
+        );
+        assert_eq!(
+            reports[3].to_string(),
+            "guard at (4, 1) facing Left visits 2 position(s) and leaves the map"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_search_matches_sequential() -> anyhow::Result<()> {
+        for input in [INPUT_1, INPUT_2] {
+            let lab_input = LabInput::from_str(input)?;
+            let candidates = lab_input
+                .patrol_position_path()
+                .into_iter()
+                .filter(|pos| *pos != lab_input.guard().position)
+                .collect::<Vec<_>>();
+
+            let sequential = count_loop_obstructions(&lab_input, &candidates, 1);
+            for threads in [2, 3, 64] {
+                assert_eq!(
+                    count_loop_obstructions(&lab_input, &candidates, threads),
+                    sequential
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
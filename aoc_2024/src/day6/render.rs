@@ -0,0 +1,317 @@
+//! Draws the guard's patrol over the lab map, either as plain text or with ANSI colours.
+
+use super::{Guard, GuardDirection, LabInput, MapTileType, Position};
+use anyhow::ensure;
+use common::grid::Grid;
+use fxhash::FxHashSet;
+use std::fmt::Write;
+
+/// How to draw a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// Plain text, suitable for writing to files.
+    #[default]
+    Plain,
+    /// Coloured with ANSI escape codes, for terminals.
+    Ansi,
+}
+
+/// One step of the patrol: where the guard is and which way it faces afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatrolStep {
+    pub position: Position,
+    pub direction: GuardDirection,
+    /// Whether the guard turned in place instead of moving.
+    pub turned: bool,
+}
+
+/// A guard's patrol simulated step by step, optionally with an extra obstruction.
+pub struct Patrol<'a> {
+    lab_input: &'a LabInput,
+    guard: Guard,
+    extra_obstruction: Option<Position>,
+    steps: Vec<PatrolStep>,
+    is_loop: bool,
+}
+
+/// How the guard crossed a cell.
+#[derive(Debug, Clone, Copy, Default)]
+struct Trail {
+    vertical: bool,
+    horizontal: bool,
+    turned: bool,
+}
+
+impl Trail {
+    fn cross(&mut self, direction: GuardDirection) {
+        match direction {
+            GuardDirection::Up | GuardDirection::Down => self.vertical = true,
+            GuardDirection::Left | GuardDirection::Right => self.horizontal = true,
+        }
+    }
+
+    fn glyph(&self) -> Option<char> {
+        match (self.vertical, self.horizontal) {
+            _ if self.turned => Some('+'),
+            (true, true) => Some('+'),
+            (true, false) => Some('|'),
+            (false, true) => Some('-'),
+            (false, false) => None,
+        }
+    }
+}
+
+impl<'a> Patrol<'a> {
+    /// The patrol of the puzzle's guard.
+    pub fn new(
+        lab_input: &'a LabInput,
+        extra_obstruction: Option<Position>,
+    ) -> anyhow::Result<Self> {
+        Self::for_guard(lab_input, lab_input.guard(), extra_obstruction)
+    }
+
+    /// The patrol of `guard`, ignoring any other guard on the map. Fails if `extra_obstruction`
+    /// is outside the map.
+    pub fn for_guard(
+        lab_input: &'a LabInput,
+        guard: Guard,
+        extra_obstruction: Option<Position>,
+    ) -> anyhow::Result<Self> {
+        let grid = &lab_input.grid;
+        if let Some(Position { row, col }) = extra_obstruction {
+            ensure!(
+                row < grid.height() && col < grid.width(),
+                "obstruction at ({row}, {col}) is outside the {}x{} map",
+                grid.height(),
+                grid.width()
+            );
+        }
+        let mut steps = Vec::new();
+        let mut seen = FxHashSet::default();
+        let (mut position, mut direction) = guard.corner();
+        seen.insert((position, direction));
+        let mut is_loop = false;
+        while let Some(next) = lab_input.grid.offset(position, direction.offset()) {
+            let next = Position::from(next);
+            let turned =
+                extra_obstruction == Some(next) || lab_input.grid[next] == MapTileType::Obstructed;
+            if turned {
+                direction = direction.turn_right();
+            } else {
+                position = next;
+            }
+            steps.push(PatrolStep {
+                position,
+                direction,
+                turned,
+            });
+            if !seen.insert((position, direction)) {
+                is_loop = true;
+                break;
+            }
+        }
+        Ok(Patrol {
+            lab_input,
+            guard,
+            extra_obstruction,
+            steps,
+            is_loop,
+        })
+    }
+
+    pub fn steps(&self) -> &[PatrolStep] {
+        &self.steps
+    }
+
+    /// Whether the patrol ended because the guard got stuck in a loop rather than leaving.
+    pub fn is_loop(&self) -> bool {
+        self.is_loop
+    }
+
+    /// Draws the trail of the whole patrol.
+    pub fn render(&self, style: Style) -> String {
+        self.draw(self.steps.len(), false, style)
+    }
+
+    /// Draws the patrol after its first `steps` steps, with the guard at its position then.
+    pub fn render_frame(&self, steps: usize, style: Style) -> String {
+        self.draw(steps, true, style)
+    }
+
+    fn draw(&self, steps: usize, show_guard: bool, style: Style) -> String {
+        let grid = &self.lab_input.grid;
+        let mut trails = Grid::filled(grid.width(), grid.height(), Trail::default());
+        let Guard {
+            position: start,
+            direction: start_direction,
+        } = self.guard;
+        trails[start].cross(start_direction);
+        for step in &self.steps[..steps.min(self.steps.len())] {
+            let trail = &mut trails[step.position];
+            trail.turned |= step.turned;
+            trail.cross(step.direction);
+        }
+        let guard = match steps.checked_sub(1).and_then(|idx| self.steps.get(idx)) {
+            Some(step) => (step.position, step.direction),
+            None => (start, start_direction),
+        };
+        let guard = show_guard.then_some(guard);
+
+        let mut out = String::with_capacity((grid.width() + 1) * grid.height());
+        for (row, tiles) in grid.rows().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                let pos = Position::new(row, col);
+                let (glyph, colour) =
+                    if let Some((_, direction)) = guard.filter(|(position, _)| *position == pos) {
+                        (guard_glyph(direction), GUARD)
+                    } else if Some(pos) == self.extra_obstruction {
+                        ('O', ADDED_OBSTRUCTION)
+                    } else if *tile == MapTileType::Obstructed {
+                        ('#', OBSTRUCTION)
+                    } else if pos == start {
+                        (guard_glyph(start_direction), START)
+                    } else if let MapTileType::Guard(direction) = tile {
+                        (guard_glyph(*direction), START)
+                    } else if let Some(glyph) = trails[pos].glyph() {
+                        (glyph, TRAIL)
+                    } else {
+                        ('.', EMPTY)
+                    };
+                match style {
+                    Style::Plain => out.push(glyph),
+                    Style::Ansi => {
+                        write!(out, "{colour}{glyph}{RESET}").expect("writing to a String")
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+const GUARD: &str = "\x1b[1;32m";
+const START: &str = "\x1b[32m";
+const ADDED_OBSTRUCTION: &str = "\x1b[1;31m";
+const OBSTRUCTION: &str = "\x1b[1;37m";
+const TRAIL: &str = "\x1b[33m";
+const EMPTY: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+fn guard_glyph(direction: GuardDirection) -> char {
+    match direction {
+        GuardDirection::Up => '^',
+        GuardDirection::Right => '>',
+        GuardDirection::Down => 'v',
+        GuardDirection::Left => '<',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{INPUT_1, INPUT_2};
+    use super::*;
+
+    #[test]
+    fn test_render_with_obstruction() -> anyhow::Result<()> {
+        let lab_input = INPUT_1.parse::<LabInput>()?;
+
+        let patrol = Patrol::new(&lab_input, Some(Position::new(6, 3)))?;
+        assert!(patrol.is_loop());
+        assert_eq!(
+            patrol.render(Style::Plain),
+            "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O^---+.
+........#.
+#.........
+......#...
+"
+        );
+
+        let patrol = Patrol::new(&lab_input, Some(Position::new(7, 6)))?;
+        assert_eq!(
+            patrol.render(Style::Plain),
+            "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+..+-+-+#|.
+..|.|.|.|.
+.#+-^-+-+.
+......O.#.
+#.........
+......#...
+"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_obstruction_outside_map() -> anyhow::Result<()> {
+        let lab_input = INPUT_1.parse::<LabInput>()?;
+
+        let err = Patrol::new(&lab_input, Some(Position::new(3, 10))).err();
+        assert_eq!(
+            err.map(|e| e.to_string()),
+            Some("obstruction at (3, 10) is outside the 10x10 map".to_string())
+        );
+        assert!(Patrol::new(&lab_input, Some(Position::new(9, 9))).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_frames() -> anyhow::Result<()> {
+        let lab_input = INPUT_2.parse::<LabInput>()?;
+        let patrol = Patrol::new(&lab_input, None)?;
+
+        assert!(!patrol.is_loop());
+        assert_eq!(
+            patrol.render_frame(0, Style::Plain).lines().nth(6),
+            Some(".#..^.....")
+        );
+        assert_eq!(
+            patrol.render_frame(2, Style::Plain).lines().nth(4),
+            Some("....^..#..")
+        );
+        // The guard turns twice in place at the double obstruction before leaving upwards
+        let last = patrol.render(Style::Plain);
+        assert_eq!(last.lines().next(), Some("....#...|."));
+        assert_eq!(last.lines().nth(4), Some("....|..#+."));
+        assert_eq!(last.lines().nth(6), Some(".#..^....."));
+
+        let coloured = patrol.render(Style::Ansi);
+        assert!(coloured.contains(&format!("{TRAIL}+{RESET}")));
+        assert_eq!(coloured.matches(RESET).count(), 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_other_guard() -> anyhow::Result<()> {
+        let lab_input = ".#...\n....#\n.^...\n#..v.\n.<.#.".parse::<LabInput>()?;
+
+        let patrol = Patrol::for_guard(&lab_input, lab_input.guards()[1], None)?;
+        assert!(patrol.is_loop());
+        assert_eq!(
+            patrol.render(Style::Plain),
+            "\
+.#...
+.+-+#
+.^.|.
+#+-v.
+.<.#.
+"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use common::{Part, SolveReport};
+use std::io::Read;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod report;
+pub mod verify;
+
+/// Type-erased entry point of a day: parses the raw puzzle input and solves the given parts.
+pub type SolveFn = fn(&str, &[Part]) -> anyhow::Result<SolveReport>;
+
+/// Entry point of a day that can solve its input while streaming it from a reader.
+pub type SolveReaderFn = fn(&mut dyn Read, &[Part]) -> anyhow::Result<SolveReport>;
+
+pub struct Solver {
+    pub day: u8,
+    pub solve: SolveFn,
+    /// Used instead of `solve` for input read from stdin, when the day supports streaming.
+    pub solve_reader: Option<SolveReaderFn>,
+}
+
+macro_rules! solver {
+    ($day:literal, $solution:ty) => {
+        Solver {
+            day: $day,
+            solve: common::solve::<$solution>,
+            solve_reader: None,
+        }
+    };
+    ($day:literal, $solution:ty, $solve_reader:path) => {
+        Solver {
+            day: $day,
+            solve: common::solve::<$solution>,
+            solve_reader: Some($solve_reader),
+        }
+    };
+}
+
+/// Registry of all the solved days, ordered by day.
+pub const SOLVERS: &[Solver] = &[
+    solver!(1, day1::Day1),
+    solver!(2, day2::Day2),
+    solver!(3, day3::Day3, day3::solve_reader),
+    solver!(4, day4::Day4),
+    solver!(5, day5::Day5),
+    solver!(6, day6::Day6),
+];
+
+pub fn solver(day: u8) -> Option<&'static Solver> {
+    SOLVERS.iter().find(|solver| solver.day == day)
+}
+
+/// Builds the answer of one part for the report tests.
+#[cfg(test)]
+fn part_answer(part: Part, answer: &str, elapsed: std::time::Duration) -> common::PartAnswer {
+    common::PartAnswer {
+        part,
+        answer: answer.to_string(),
+        elapsed,
+    }
+}
@@ -0,0 +1,143 @@
+use common::{Part, SolveReport};
+use serde_json::json;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: u8,
+    pub report: SolveReport,
+}
+
+/// The stages reported for every day: parse, part 1, part 2 and the total.
+type StageTimings = [Option<Duration>; 4];
+
+fn stage_timings(report: &SolveReport) -> StageTimings {
+    let part_elapsed = |part| report.answer(part).map(|answer| answer.elapsed);
+    [
+        Some(report.parse_elapsed),
+        part_elapsed(Part::One),
+        part_elapsed(Part::Two),
+        Some(report.total_elapsed()),
+    ]
+}
+
+fn table_row(label: &str, cells: &[String]) -> String {
+    let cells = cells
+        .iter()
+        .map(|cell| format!("{cell:>12}"))
+        .collect::<Vec<_>>();
+    format!("{label:>5} {}\n", cells.join(" "))
+}
+
+fn format_timings(timings: &StageTimings) -> Vec<String> {
+    timings
+        .iter()
+        .map(|elapsed| elapsed.map_or_else(|| "-".to_string(), |e| format!("{e:.2?}")))
+        .collect()
+}
+
+/// Renders the per-stage timings of every day as a table, followed by a row with the totals.
+pub fn render_table(reports: &[DayReport]) -> String {
+    let header = ["parse", "part 1", "part 2", "total"].map(String::from);
+    let mut table = table_row("day", &header);
+    let mut totals: StageTimings = [None; 4];
+    for DayReport { day, report } in reports {
+        let timings = stage_timings(report);
+        for (total, elapsed) in totals.iter_mut().zip(timings) {
+            if let Some(elapsed) = elapsed {
+                *total = Some(total.unwrap_or_default() + elapsed);
+            }
+        }
+        table.push_str(&table_row(&day.to_string(), &format_timings(&timings)));
+    }
+    table.push_str(&table_row("total", &format_timings(&totals)));
+    table
+}
+
+/// Renders the answers and timings as JSON; all durations are reported in nanoseconds.
+pub fn render_json(reports: &[DayReport]) -> String {
+    let days = reports
+        .iter()
+        .map(|DayReport { day, report }| {
+            let parts = report
+                .answers
+                .iter()
+                .map(|answer| {
+                    json!({
+                        "part": u8::from(answer.part),
+                        "answer": answer.answer,
+                        "elapsed_ns": answer.elapsed.as_nanos() as u64,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "day": day,
+                "parse_ns": report.parse_elapsed.as_nanos() as u64,
+                "parts": parts,
+                "total_ns": report.total_elapsed().as_nanos() as u64,
+            })
+        })
+        .collect::<Vec<_>>();
+    let total_elapsed = reports
+        .iter()
+        .map(|day_report| day_report.report.total_elapsed())
+        .sum::<Duration>();
+    json!({
+        "days": days,
+        "total_ns": total_elapsed.as_nanos() as u64,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_answer;
+
+    fn reports() -> Vec<DayReport> {
+        let answer =
+            |part, answer, millis| part_answer(part, answer, Duration::from_millis(millis));
+        vec![
+            DayReport {
+                day: 1,
+                report: SolveReport {
+                    parse_elapsed: Duration::from_millis(1),
+                    answers: vec![answer(Part::One, "11", 2), answer(Part::Two, "31", 3)],
+                },
+            },
+            DayReport {
+                day: 2,
+                report: SolveReport {
+                    parse_elapsed: Duration::from_millis(4),
+                    answers: vec![answer(Part::Two, "4", 5)],
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_table() {
+        let table = render_table(&reports());
+        let rows = table.lines().map(str::split_whitespace);
+        let rows = rows.map(Iterator::collect::<Vec<_>>).collect::<Vec<_>>();
+
+        assert_eq!(rows[1], vec!["1", "1.00ms", "2.00ms", "3.00ms", "6.00ms"]);
+        assert_eq!(rows[2], vec!["2", "4.00ms", "-", "5.00ms", "9.00ms"]);
+        assert_eq!(
+            rows[3],
+            vec!["total", "5.00ms", "2.00ms", "8.00ms", "15.00ms"]
+        );
+    }
+
+    #[test]
+    fn test_render_json() -> anyhow::Result<()> {
+        let json = serde_json::from_str::<serde_json::Value>(&render_json(&reports()))?;
+
+        assert_eq!(json["total_ns"], 15_000_000);
+        assert_eq!(json["days"][0]["parts"][1]["answer"], "31");
+        assert_eq!(json["days"][1]["parts"][0]["part"], 2);
+        assert_eq!(json["days"][1]["parse_ns"], 4_000_000);
+
+        Ok(())
+    }
+}
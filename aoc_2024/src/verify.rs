@@ -0,0 +1,169 @@
+use anyhow::{bail, Context};
+use common::{Part, SolveReport};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Name of the file, kept next to the inputs, holding the expected answers.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Deserialize)]
+struct AnswersFile {
+    #[serde(default)]
+    answers: Vec<AnswerEntry>,
+}
+
+#[derive(Deserialize)]
+struct AnswerEntry {
+    day: u8,
+    part: u8,
+    expected: toml::Value,
+}
+
+/// Expected answers keyed by day and part.
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    answers: HashMap<(u8, Part), String>,
+}
+
+impl FromStr for ExpectedAnswers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file: AnswersFile = toml::from_str(s).context("Could not parse answers")?;
+        let mut answers = HashMap::with_capacity(file.answers.len());
+        for AnswerEntry {
+            day,
+            part,
+            expected,
+        } in file.answers
+        {
+            let part = Part::try_from(part)?;
+            // Answers are compared in their rendered form, so accept both `123` and `"123"`
+            let expected = match expected {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(i) => i.to_string(),
+                other => bail!("unsupported answer for day {day} part {part}: {other}"),
+            };
+            if answers.insert((day, part), expected).is_some() {
+                bail!("duplicate answer for day {day} part {part}");
+            }
+        }
+        Ok(ExpectedAnswers { answers })
+    }
+}
+
+impl ExpectedAnswers {
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {expected})"),
+            Verdict::Missing => write!(f, "missing expected answer"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartVerdict {
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Checks every answer of `report` against the expected answers of `day`.
+pub fn verify(day: u8, report: &SolveReport, expected: &ExpectedAnswers) -> Vec<PartVerdict> {
+    report
+        .answers
+        .iter()
+        .map(|answer| {
+            let verdict = match expected.get(day, answer.part) {
+                Some(expected) if expected == answer.answer => Verdict::Pass,
+                Some(expected) => Verdict::Fail {
+                    expected: expected.to_string(),
+                },
+                None => Verdict::Missing,
+            };
+            PartVerdict {
+                part: answer.part,
+                answer: answer.answer.clone(),
+                verdict,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_answer;
+    use std::time::Duration;
+
+    const ANSWERS: &str = r#"
+[[answers]]
+day = 1
+part = 1
+expected = 11
+
+[[answers]]
+day = 1
+part = 2
+expected = "31"
+"#;
+
+    #[test]
+    fn test_expected_answers_from_str() -> anyhow::Result<()> {
+        let expected = ExpectedAnswers::from_str(ANSWERS)?;
+
+        assert_eq!(expected.get(1, Part::One), Some("11"));
+        assert_eq!(expected.get(1, Part::Two), Some("31"));
+        assert_eq!(expected.get(2, Part::One), None);
+
+        let duplicated = format!("{ANSWERS}{ANSWERS}");
+        assert!(ExpectedAnswers::from_str(&duplicated).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify() -> anyhow::Result<()> {
+        let expected = ExpectedAnswers::from_str(ANSWERS)?;
+        let answer = |part, answer| part_answer(part, answer, Duration::ZERO);
+        let report = SolveReport {
+            parse_elapsed: Duration::ZERO,
+            answers: vec![answer(Part::One, "11"), answer(Part::Two, "30")],
+        };
+
+        let verdicts = verify(1, &report, &expected)
+            .into_iter()
+            .map(|part_verdict| part_verdict.verdict)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            verdicts,
+            vec![
+                Verdict::Pass,
+                Verdict::Fail {
+                    expected: "31".to_string()
+                }
+            ]
+        );
+
+        let verdicts = verify(2, &report, &expected);
+        assert!(verdicts.iter().all(|v| v.verdict == Verdict::Missing));
+
+        Ok(())
+    }
+}
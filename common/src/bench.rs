@@ -0,0 +1,134 @@
+use crate::time;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+pub use std::hint::black_box;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Number of untimed runs used to warm up caches and the branch predictor
+    pub warmup: usize,
+    /// Number of timed runs the statistics are computed from
+    pub samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 10,
+            samples: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchStats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl BenchStats {
+    /// Computes the statistics of the given samples, or `None` when there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let mean_secs = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            sorted
+                .iter()
+                .map(|sample| (sample.as_secs_f64() - mean_secs).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        // nearest-rank percentile
+        let p95 = sorted[(n * 95).div_ceil(100) - 1];
+        Some(BenchStats {
+            samples: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean_secs),
+            p95,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+impl Display for BenchStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, mean {:.2?}, p95 {:.2?}, stddev {:.2?} ({} samples)",
+            self.min, self.median, self.mean, self.p95, self.stddev, self.samples
+        )
+    }
+}
+
+/// Runs `func` `config.warmup` times and then times `config.samples` further runs. The output
+/// of every run goes through [`black_box`] so that the optimizer can't elide the work.
+///
+/// # Panics
+///
+/// Panics when `config.samples` is zero.
+pub fn bench<F, T>(config: &BenchConfig, mut func: F) -> BenchStats
+where
+    F: FnMut() -> T,
+{
+    for _ in 0..config.warmup {
+        black_box(func());
+    }
+    let samples = (0..config.samples)
+        .map(|_| time(|| black_box(func())).elapsed())
+        .collect::<Vec<_>>();
+    BenchStats::from_samples(&samples).expect("benchmark needs at least one sample")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_samples() {
+        let samples = (1..=20)
+            .rev()
+            .map(Duration::from_millis)
+            .collect::<Vec<_>>();
+        let stats = BenchStats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.samples, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.stddev.as_micros(), 5_916);
+        assert!(BenchStats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_bench_runs_warmup_and_samples() {
+        let mut runs = 0;
+        let config = BenchConfig {
+            warmup: 3,
+            samples: 7,
+        };
+        let stats = bench(&config, || runs += 1);
+
+        assert_eq!(runs, 10);
+        assert_eq!(stats.samples, 7);
+        assert!(stats.min <= stats.median && stats.median <= stats.p95);
+    }
+}
@@ -0,0 +1,147 @@
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-size set of the integers `0..len`, one bit each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        BitSet {
+            len,
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `value` is outside the set's range.
+    pub fn insert(&mut self, value: usize) {
+        assert!(value < self.len, "value {value} is outside the set");
+        self.words[value / WORD_BITS] |= 1 << (value % WORD_BITS);
+    }
+
+    /// Whether `value` is in the set; always `false` outside the set's range.
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.len && self.words[value / WORD_BITS] & (1 << (value % WORD_BITS)) != 0
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Whether the sets share any value. Both sets must have the same range.
+    pub fn intersects(&self, other: &BitSet) -> bool {
+        intersects(&self.words, &other.words)
+    }
+}
+
+/// A square matrix of bits, e.g. the adjacency matrix of a graph over the nodes `0..len`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    len: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix {
+    pub fn new(len: usize) -> Self {
+        let words_per_row = len.div_ceil(WORD_BITS);
+        BitMatrix {
+            len,
+            words_per_row,
+            words: vec![0; len * words_per_row],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if either index is outside the matrix.
+    pub fn set(&mut self, row: usize, col: usize) {
+        assert!(col < self.len, "column {col} is outside the matrix");
+        let start = row * self.words_per_row;
+        self.words[start + col / WORD_BITS] |= 1 << (col % WORD_BITS);
+    }
+
+    /// Whether the bit at `(row, col)` is set; always `false` outside the matrix.
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        row < self.len
+            && col < self.len
+            && self.row(row)[col / WORD_BITS] & (1 << (col % WORD_BITS)) != 0
+    }
+
+    /// Whether `row` has a bit set at any of the values in `set`, which must have the same
+    /// range as the matrix. Always `false` for rows outside the matrix.
+    pub fn row_intersects(&self, row: usize, set: &BitSet) -> bool {
+        row < self.len && intersects(self.row(row), &set.words)
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        let start = row * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+}
+
+fn intersects(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).any(|(a, b)| a & b != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::new(130);
+        set.insert(3);
+        set.insert(129);
+
+        assert!(set.contains(3) && set.contains(129));
+        assert!(!set.contains(64) && !set.contains(1000));
+
+        let mut other = BitSet::new(130);
+        other.insert(128);
+        assert!(!set.intersects(&other));
+        other.insert(129);
+        assert!(set.intersects(&other));
+
+        set.clear();
+        assert!(!set.contains(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "value 150 is outside the set")]
+    fn test_bitset_insert_out_of_range() {
+        BitSet::new(130).insert(150);
+    }
+
+    #[test]
+    fn test_bit_matrix() {
+        let mut matrix = BitMatrix::new(70);
+        matrix.set(2, 65);
+        matrix.set(69, 0);
+
+        assert!(matrix.get(2, 65) && matrix.get(69, 0));
+        assert!(!matrix.get(65, 2) && !matrix.get(70, 0));
+
+        let mut set = BitSet::new(70);
+        set.insert(65);
+        assert!(matrix.row_intersects(2, &set));
+        assert!(!matrix.row_intersects(69, &set));
+        assert!(!matrix.row_intersects(100, &set));
+    }
+}
@@ -0,0 +1,167 @@
+//! Cycle detection over sequences produced by repeatedly applying a step function to a state.
+//! A step returning `None` ends the sequence, in which case there is no cycle.
+
+use fxhash::FxHashMap;
+use std::hash::Hash;
+
+/// Shape of the cycle of a sequence `x0, x1, ...`: the states from index `start` onwards repeat
+/// every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Detects a cycle by remembering the index of every state seen. Needs memory proportional to
+/// `start + length` but calls `step` only once per state.
+pub fn find_cycle_hashed<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen = FxHashMap::default();
+    let mut state = initial;
+    for idx in 0.. {
+        if let Some(start) = seen.insert(state.clone(), idx) {
+            return Some(Cycle {
+                start,
+                length: idx - start,
+            });
+        }
+        state = step(&state)?;
+    }
+    unreachable!("sequence longer than usize::MAX")
+}
+
+/// Detects a cycle using Floyd's tortoise and hare in constant memory.
+pub fn find_cycle_floyd<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        hare = step(&hare)?;
+    }
+
+    // The distance between the two is now a multiple of the cycle length, so walking both at the
+    // same pace from the start and from the meeting point makes them meet at the cycle start.
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+/// Detects a cycle using Brent's algorithm in constant memory; usually needs fewer steps than
+/// Floyd's.
+pub fn find_cycle_brent<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: PartialEq + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // Put the hare `length` steps ahead and then walk both until they meet at the cycle start
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+/// Returns the state after `n` steps from `initial`, skipping over whole cycles once one is
+/// detected so that `n` can be far larger than the number of distinct states. Returns `None` if
+/// the sequence ends before `n` steps.
+pub fn state_after<S, F>(initial: S, mut step: F, n: usize) -> Option<S>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut history = Vec::new();
+    let mut seen = FxHashMap::default();
+    let mut state = initial;
+    for idx in 0..n {
+        if let Some(start) = seen.insert(state.clone(), idx) {
+            let length = idx - start;
+            return Some(history.swap_remove(start + (n - start) % length));
+        }
+        history.push(state.clone());
+        state = step(&state)?;
+    }
+    Some(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 -> 10 -> 101 -> 2 -> 5 -> 26 -> 167 -> 95 -> 101 -> ... i.e. a cycle of length 6 starting
+    // at index 2
+    fn step(x: &u64) -> Option<u64> {
+        Some((x * x + 1) % 255)
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Some(Cycle {
+            start: 2,
+            length: 6,
+        });
+
+        assert_eq!(find_cycle_hashed(3, step), expected);
+        assert_eq!(find_cycle_floyd(3, step), expected);
+        assert_eq!(find_cycle_brent(3, step), expected);
+    }
+
+    #[test]
+    fn test_find_cycle_without_cycle() {
+        let step = |x: &u64| x.checked_sub(1);
+
+        assert_eq!(find_cycle_hashed(10, step), None);
+        assert_eq!(find_cycle_floyd(10, step), None);
+        assert_eq!(find_cycle_brent(10, step), None);
+    }
+
+    #[test]
+    fn test_state_after() {
+        assert_eq!(state_after(3, step, 0), Some(3));
+        assert_eq!(state_after(3, step, 2), Some(101));
+        assert_eq!(state_after(3, step, 7), Some(95));
+        assert_eq!(state_after(3, step, 8), Some(101));
+        assert_eq!(state_after(3, step, 1_000_000_000), Some(5));
+        assert_eq!(state_after(3_u64, |x| x.checked_sub(1), 5), None);
+    }
+}
@@ -0,0 +1,257 @@
+use std::ops::{Add, Neg, Sub};
+
+/// A point on a 2D grid. Coordinates follow the `(row, column)` convention of
+/// [`Grid`](crate::grid::Grid), i.e. rows grow downwards and columns grow to the right.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T = usize> {
+    pub row: T,
+    pub col: T,
+}
+
+/// A signed point, typically used as an offset.
+pub type Offset = Point<isize>;
+
+impl<T> Point<T> {
+    pub const fn new(row: T, col: T) -> Self {
+        Point { row, col }
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((row, col): (T, T)) -> Self {
+        Point { row, col }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(Point { row, col }: Point<T>) -> Self {
+        (row, col)
+    }
+}
+
+macro_rules! impl_distances {
+    ($($t:ty),*) => {
+        $(
+            impl Point<$t> {
+                /// Sum of the absolute differences along both axes.
+                pub fn manhattan(&self, other: &Self) -> usize {
+                    self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+                }
+
+                /// Largest absolute difference along either axis.
+                pub fn chebyshev(&self, other: &Self) -> usize {
+                    self.row.abs_diff(other.row).max(self.col.abs_diff(other.col))
+                }
+            }
+        )*
+    };
+}
+
+impl_distances!(usize, isize);
+
+impl Point<usize> {
+    /// Adds a signed offset, returning `None` on underflow or overflow.
+    pub fn checked_add(self, offset: Offset) -> Option<Self> {
+        Some(Point {
+            row: self.row.checked_add_signed(offset.row)?,
+            col: self.col.checked_add_signed(offset.col)?,
+        })
+    }
+
+    /// Adds a signed offset, returning `None` unless the result lies within `0..bounds.row` and
+    /// `0..bounds.col`.
+    pub fn checked_add_within(self, offset: Offset, bounds: Self) -> Option<Self> {
+        self.checked_add(offset)
+            .filter(|p| p.row < bounds.row && p.col < bounds.col)
+    }
+}
+
+impl Add for Offset {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl Sub for Offset {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl Neg for Offset {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.row, -self.col)
+    }
+}
+
+/// The 4 orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    /// All the directions, clockwise starting from `Up`.
+    pub const ALL: [Direction4; 4] = [
+        Direction4::Up,
+        Direction4::Right,
+        Direction4::Down,
+        Direction4::Left,
+    ];
+
+    pub fn offset(&self) -> Offset {
+        match self {
+            Direction4::Up => Point::new(-1, 0),
+            Direction4::Right => Point::new(0, 1),
+            Direction4::Down => Point::new(1, 0),
+            Direction4::Left => Point::new(0, -1),
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::ALL[(*self as usize + 2) % 4]
+    }
+}
+
+/// The 4 orthogonal and 4 diagonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// All the directions, clockwise starting from `Up`.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    pub fn offset(&self) -> Offset {
+        match self {
+            Direction8::Up => Point::new(-1, 0),
+            Direction8::UpRight => Point::new(-1, 1),
+            Direction8::Right => Point::new(0, 1),
+            Direction8::DownRight => Point::new(1, 1),
+            Direction8::Down => Point::new(1, 0),
+            Direction8::DownLeft => Point::new(1, -1),
+            Direction8::Left => Point::new(0, -1),
+            Direction8::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    /// Rotates clockwise by 45 degrees.
+    pub fn rotate_right(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % 8]
+    }
+
+    /// Rotates anticlockwise by 45 degrees.
+    pub fn rotate_left(&self) -> Self {
+        Self::ALL[(*self as usize + 7) % 8]
+    }
+
+    /// Rotates clockwise by 90 degrees.
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(*self as usize + 2) % 8]
+    }
+
+    /// Rotates anticlockwise by 90 degrees.
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(*self as usize + 6) % 8]
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::ALL[(*self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        match direction {
+            Direction4::Up => Direction8::Up,
+            Direction4::Right => Direction8::Right,
+            Direction4::Down => Direction8::Down,
+            Direction4::Left => Direction8::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(1_usize, 5), Point::new(4_usize, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+
+        let (a, b) = (Point::new(-2_isize, 3), Point::new(2_isize, -3));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+    }
+
+    #[test]
+    fn test_checked_add_within() {
+        let bounds = Point::new(3, 4);
+        let p = Point::new(0_usize, 3);
+
+        assert_eq!(p.checked_add(Direction4::Up.offset()), None);
+        assert_eq!(
+            p.checked_add_within(Direction4::Right.offset(), bounds),
+            None
+        );
+        assert_eq!(
+            p.checked_add_within(Direction8::DownLeft.offset(), bounds),
+            Some(Point::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_turns() {
+        for direction in Direction4::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.reverse().offset(), -direction.offset());
+            assert_eq!(
+                Direction8::from(direction).turn_right(),
+                Direction8::from(direction.turn_right())
+            );
+        }
+        assert_eq!(Direction4::Left.turn_right(), Direction4::Up);
+        assert_eq!(Direction8::UpLeft.rotate_right(), Direction8::Up);
+        assert_eq!(Direction8::Up.rotate_left(), Direction8::UpLeft);
+        for direction in Direction8::ALL {
+            assert_eq!(direction.reverse().offset(), -direction.offset());
+            assert_eq!(direction.rotate_right().rotate_left(), direction);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// A directed graph over the nodes `0..len`, stored as adjacency lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiGraph {
+    successors: Vec<Vec<usize>>,
+}
+
+/// The nodes of a cycle, in the order the edges run, found while sorting a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    pub nodes: Vec<usize>,
+}
+
+impl Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph contains a cycle through nodes {:?}", self.nodes)
+    }
+}
+
+impl Error for CycleError {}
+
+impl DiGraph {
+    pub fn new(len: usize) -> Self {
+        DiGraph {
+            successors: vec![Vec::new(); len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    /// Adds the edge `from -> to`. Panics if either node is out of range.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        assert!(to < self.len(), "node {to} is outside the graph");
+        self.successors[from].push(to);
+    }
+
+    pub fn successors(&self, node: usize) -> &[usize] {
+        &self.successors[node]
+    }
+
+    /// Orders the nodes so that every edge points forwards using Kahn's algorithm. The order is
+    /// deterministic: ready nodes are taken first-in first-out, starting with the sources in
+    /// index order.
+    pub fn toposort(&self) -> Result<Vec<usize>, CycleError> {
+        let mut in_degree = vec![0_usize; self.len()];
+        for &to in self.successors.iter().flatten() {
+            in_degree[to] += 1;
+        }
+
+        let mut ready = (0..self.len())
+            .filter(|&node| in_degree[node] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for &next in &self.successors[node] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&in_degree))
+        }
+    }
+
+    /// Finds a cycle among the nodes Kahn's algorithm couldn't remove. Each of them still has
+    /// a predecessor that wasn't removed either, so following those must end up in a cycle.
+    fn find_cycle(&self, in_degree: &[usize]) -> CycleError {
+        let mut predecessor = vec![None; self.len()];
+        for (from, successors) in self.successors.iter().enumerate() {
+            if in_degree[from] > 0 {
+                for &to in successors {
+                    predecessor[to] = Some(from);
+                }
+            }
+        }
+
+        let mut seen = vec![false; self.len()];
+        let mut node = (0..self.len())
+            .find(|&node| in_degree[node] > 0)
+            .expect("a cycle leaves nodes with incoming edges");
+        while !seen[node] {
+            seen[node] = true;
+            node = predecessor[node].expect("remaining nodes have a remaining predecessor");
+        }
+
+        let start = node;
+        let mut nodes = vec![start];
+        let mut node = predecessor[start].expect("nodes in a cycle have a predecessor");
+        while node != start {
+            nodes.push(node);
+            node = predecessor[node].expect("nodes in a cycle have a predecessor");
+        }
+        nodes.reverse();
+        // Start from the lowest node so the reported cycle doesn't depend on where it was found
+        let min_idx = (0..nodes.len()).min_by_key(|&idx| nodes[idx]).unwrap_or(0);
+        nodes.rotate_left(min_idx);
+        CycleError { nodes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_of(len: usize, edges: &[(usize, usize)]) -> DiGraph {
+        let mut graph = DiGraph::new(len);
+        for &(from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_toposort() {
+        let graph = graph_of(5, &[(3, 1), (1, 0), (3, 0), (4, 2)]);
+
+        assert_eq!(graph.toposort(), Ok(vec![3, 4, 1, 2, 0]));
+        assert_eq!(DiGraph::new(0).toposort(), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_toposort_reports_cycle() {
+        // 0 -> 1 -> 2 -> 3 -> 1, with 3 -> 4 hanging off the cycle
+        let graph = graph_of(5, &[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+
+        let cycle = graph.toposort().unwrap_err().nodes;
+        assert_eq!(cycle.len(), 3);
+        for (idx, &node) in cycle.iter().enumerate() {
+            let next = cycle[(idx + 1) % cycle.len()];
+            assert!(graph.successors(node).contains(&next));
+        }
+
+        let self_loop = graph_of(2, &[(1, 1)]);
+        assert_eq!(self_loop.toposort(), Err(CycleError { nodes: vec![1] }));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::geom::{Direction4, Direction8, Offset, Point};
+use crate::parse::ParseError;
+use crate::parser;
+use anyhow::bail;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// A `(row, column)` coordinate into a [`Grid`]. Every method taking a coordinate also accepts
+/// a [`Point`].
+pub type Coord = (usize, usize);
+
+/// A rectangular 2D grid stored as a single row-major `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from row-major `cells`, which must hold exactly `width * height` items.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> anyhow::Result<Self> {
+        if width * height != cells.len() {
+            bail!(
+                "expected {} cells for a {}x{} grid, got {}",
+                width * height,
+                width,
+                height,
+                cells.len()
+            );
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a grid with one row per line, mapping every character through `parse_cell`. All
+    /// the rows must have the same width.
+    pub fn parse_with<F, E>(s: &str, parse_cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: Display,
+    {
+        parser::parse_all(s, parser::grid(parse_cell))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The exclusive `(height, width)` bounds of the grid coordinates.
+    pub fn bounds(&self) -> Point {
+        Point::new(self.height, self.width)
+    }
+
+    pub fn contains(&self, pos: impl Into<Coord>) -> bool {
+        let (row, col) = pos.into();
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: impl Into<Coord>) -> Option<&T> {
+        let pos = pos.into();
+        self.contains(pos).then(|| &self.cells[self.index_of(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: impl Into<Coord>) -> Option<&mut T> {
+        let pos = pos.into();
+        if self.contains(pos) {
+            let idx = self.index_of(pos);
+            Some(&mut self.cells[idx])
+        } else {
+            None
+        }
+    }
+
+    /// Applies the `(row, column)` offset to `pos`, returning `None` if it leaves the grid.
+    pub fn offset(&self, pos: impl Into<Coord>, offset: impl Into<Offset>) -> Option<Coord> {
+        let pos = Point::from(pos.into()).checked_add_within(offset.into(), self.bounds())?;
+        Some(pos.into())
+    }
+
+    /// Applies the `(row, column)` offset to `pos`, wrapping around the edges of the grid as if
+    /// it were a torus. Panics on an empty grid.
+    pub fn offset_wrapping(&self, pos: impl Into<Coord>, offset: impl Into<Offset>) -> Coord {
+        let (row, col) = pos.into();
+        let offset = offset.into();
+        let wrap = |value: usize, delta: isize, len: usize| {
+            (value as isize + delta).rem_euclid(len as isize) as usize
+        };
+        (
+            wrap(row, offset.row, self.height),
+            wrap(col, offset.col, self.width),
+        )
+    }
+
+    /// Walks from `start` (inclusive) in steps of `step` until the edge of the grid.
+    pub fn walk(
+        &self,
+        start: impl Into<Coord>,
+        step: impl Into<Offset>,
+    ) -> impl Iterator<Item = Coord> + '_ {
+        let start = start.into();
+        let step = step.into();
+        let start = self.contains(start).then_some(start);
+        std::iter::successors(start, move |pos| self.offset(*pos, step))
+    }
+
+    /// Iterates over every cell along with its coordinate, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(idx, cell)| ((idx / width, idx % width), cell))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let cells = if col < self.width {
+            &self.cells[col..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Iterates over all the diagonals running from top-left to bottom-right.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .rev()
+            .map(|col| (0, col))
+            .chain((1..self.height).map(|row| (row, 0)));
+        starts.map(|start| self.walk(start, (1, 1)).map(|pos| &self[pos]))
+    }
+
+    /// Iterates over all the diagonals running from top-right to bottom-left.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let last_col = self.width.saturating_sub(1);
+        let starts = (0..self.width)
+            .map(|col| (0, col))
+            .chain((1..self.height).map(move |row| (row, last_col)));
+        starts.map(|start| self.walk(start, (1, -1)).map(|pos| &self[pos]))
+    }
+
+    /// Iterates over the in-bounds orthogonal neighbours of `pos`, clockwise starting from the
+    /// top.
+    pub fn neighbours4(&self, pos: impl Into<Coord>) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(
+            pos.into(),
+            Direction4::ALL.map(|direction| direction.offset()),
+        )
+    }
+
+    /// Iterates over the in-bounds orthogonal and diagonal neighbours of `pos`, clockwise
+    /// starting from the top.
+    pub fn neighbours8(&self, pos: impl Into<Coord>) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(
+            pos.into(),
+            Direction8::ALL.map(|direction| direction.offset()),
+        )
+    }
+
+    fn neighbours<const N: usize>(
+        &self,
+        pos: Coord,
+        offsets: [Offset; N],
+    ) -> impl Iterator<Item = (Coord, &T)> {
+        offsets
+            .into_iter()
+            .filter_map(move |offset| self.offset(pos, offset))
+            .map(|pos| (pos, &self[pos]))
+    }
+
+    /// Returns the coordinate of the first cell, row by row, matching `predicate`.
+    pub fn find<P>(&self, mut predicate: P) -> Option<Coord>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find_map(|(pos, cell)| predicate(cell).then_some(pos))
+    }
+
+    fn index_of(&self, (row, col): Coord) -> usize {
+        row * self.width + col
+    }
+}
+
+impl<T, P: Into<Coord>> Index<P> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: P) -> &Self::Output {
+        let pos = pos.into();
+        assert!(self.contains(pos), "{pos:?} is outside the grid");
+        &self.cells[self.index_of(pos)]
+    }
+}
+
+impl<T, P: Into<Coord>> IndexMut<P> for Grid<T> {
+    fn index_mut(&mut self, pos: P) -> &mut Self::Output {
+        let pos = pos.into();
+        assert!(self.contains(pos), "{pos:?} is outside the grid");
+        let idx = self.index_of(pos);
+        &mut self.cells[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse_with("abc\ndef", anyhow::Ok).unwrap()
+    }
+
+    fn collect<'a>(lines: impl Iterator<Item = impl Iterator<Item = &'a char>>) -> Vec<String> {
+        lines.map(|line| line.collect()).collect()
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let grid = grid();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((1, 2)), Some(&'f'));
+        assert_eq!(grid.get((2, 0)), None);
+        let err = Grid::parse_with("ab\nc", anyhow::Ok).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = Grid::parse_with("12\n3a", |c| c.to_digit(10).ok_or("not a digit")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (2, 2, "not a digit")
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = grid();
+
+        assert_eq!(grid.row(1), Some(['d', 'e', 'f'].as_slice()));
+        assert_eq!(
+            collect(grid.rows().map(|row| row.iter())),
+            vec!["abc", "def"]
+        );
+        assert_eq!(collect(grid.columns()), vec!["ad", "be", "cf"]);
+        assert_eq!(collect(grid.diagonals()), vec!["c", "bf", "ae", "d"]);
+        assert_eq!(collect(grid.anti_diagonals()), vec!["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn test_neighbours_and_find() {
+        let mut grid = grid();
+
+        let neighbours = grid
+            .neighbours4((0, 0))
+            .map(|(_, c)| *c)
+            .collect::<String>();
+        assert_eq!(neighbours, "bd");
+        let neighbours = grid
+            .neighbours8((1, 1))
+            .map(|(_, c)| *c)
+            .collect::<String>();
+        assert_eq!(neighbours, "bcfda");
+        assert_eq!(
+            grid.walk((0, 0), (1, 1)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1)]
+        );
+
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset_wrapping((0, 0), (-1, 0)), (1, 0));
+        assert_eq!(grid.offset_wrapping((1, 2), (3, 2)), (0, 1));
+
+        assert_eq!(grid.find(|c| *c == 'e'), Some((1, 1)));
+        *grid.get_mut((1, 1)).unwrap() = 'x';
+        assert_eq!(grid.find(|c| *c == 'e'), None);
+        assert_eq!(grid[(1, 1)], 'x');
+    }
+}
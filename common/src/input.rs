@@ -0,0 +1,135 @@
+use anyhow::{bail, Context};
+use std::fmt::Write;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{env, fs, io};
+
+/// Environment variable pointing at a directory holding `dayN.txt` inputs.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// An explicitly requested input, either a file or `-` for stdin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl FromStr for InputSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => bail!("input path must not be empty"),
+            "-" => Ok(InputSource::Stdin),
+            path => Ok(InputSource::File(PathBuf::from(path))),
+        }
+    }
+}
+
+impl InputSource {
+    pub fn read(&self) -> anyhow::Result<String> {
+        match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Could not read input from stdin")?;
+                Ok(input)
+            }
+            InputSource::File(path) => fs::read_to_string(path)
+                .with_context(|| format!("Could not read input {}", path.display())),
+        }
+    }
+}
+
+/// Resolves the input of a day by probing a list of directories in priority order.
+#[derive(Debug, Clone)]
+pub struct InputLocator {
+    search_dirs: Vec<PathBuf>,
+}
+
+impl InputLocator {
+    /// Builds a locator which looks into `$AOC_INPUT_DIR` (when set) and then into the `inputs`
+    /// directory of the given crate manifest dir, so that lookups work irrespective of the current
+    /// working directory. Callers typically pass `env!("CARGO_MANIFEST_DIR")`.
+    pub fn new(manifest_dir: impl AsRef<Path>) -> Self {
+        let mut search_dirs = Vec::new();
+        if let Some(dir) = env::var_os(INPUT_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            search_dirs.push(PathBuf::from(dir));
+        }
+        search_dirs.push(manifest_dir.as_ref().join("inputs"));
+        InputLocator { search_dirs }
+    }
+
+    pub fn with_dirs(search_dirs: Vec<PathBuf>) -> Self {
+        InputLocator { search_dirs }
+    }
+
+    pub fn candidates(&self, file_name: &str) -> Vec<PathBuf> {
+        self.search_dirs
+            .iter()
+            .map(|dir| dir.join(file_name))
+            .collect()
+    }
+
+    /// Returns the first existing `file_name` among the search directories.
+    pub fn locate(&self, file_name: &str) -> anyhow::Result<PathBuf> {
+        let candidates = self.candidates(file_name);
+        if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+            return Ok(path.clone());
+        }
+        let mut message = format!("Could not find {file_name}; tried:");
+        for path in &candidates {
+            write!(message, "\n  {}", path.display())?;
+        }
+        bail!(message)
+    }
+
+    /// Reads the input of `day`, preferring `source` when one was explicitly provided.
+    pub fn read(&self, day: u8, source: Option<&InputSource>) -> anyhow::Result<String> {
+        if let Some(source) = source {
+            return source.read();
+        }
+        let path = self
+            .locate(&format!("day{day}.txt"))
+            .with_context(|| format!("Could not find input for day {day}"))?;
+        InputSource::File(path).read()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_source_from_str() -> anyhow::Result<()> {
+        assert_eq!(InputSource::from_str("-")?, InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_str("inputs/day1.txt")?,
+            InputSource::File(PathBuf::from("inputs/day1.txt"))
+        );
+        assert!(InputSource::from_str("").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_falls_through_search_dirs() -> anyhow::Result<()> {
+        let root = env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        let (missing, present) = (root.join("missing"), root.join("present"));
+        fs::create_dir_all(&present)?;
+        fs::write(present.join("day1.txt"), "1 2")?;
+
+        let locator = InputLocator::with_dirs(vec![missing.clone(), present.clone()]);
+        let input = locator.read(1, None);
+        let err = format!("{:#}", locator.read(2, None).unwrap_err());
+        fs::remove_dir_all(&root)?;
+
+        assert_eq!(input?, "1 2");
+        assert!(err.contains(&missing.join("day2.txt").display().to_string()));
+        assert!(err.contains(&present.join("day2.txt").display().to_string()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use fxhash::FxHashMap;
+use std::hash::Hash;
+
+/// Maps values to dense ids `0..len` in the order they are first seen, so they can be stored in
+/// vectors and bitsets instead of hash maps.
+#[derive(Debug, Clone)]
+pub struct Interner<T> {
+    ids: FxHashMap<T, usize>,
+    values: Vec<T>,
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Interner {
+            ids: FxHashMap::default(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Interner<T> {
+    /// Returns the id of `value`, assigning the next free one if it wasn't seen before.
+    pub fn intern(&mut self, value: T) -> usize {
+        if let Some(&id) = self.ids.get(&value) {
+            return id;
+        }
+        let id = self.values.len();
+        self.ids.insert(value.clone(), id);
+        self.values.push(value);
+        id
+    }
+
+    pub fn get(&self, value: &T) -> Option<usize> {
+        self.ids.get(value).copied()
+    }
+
+    /// The value with the given id. Panics if the id wasn't handed out by this interner.
+    pub fn resolve(&self, id: usize) -> &T {
+        &self.values[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = Interner::default();
+
+        assert_eq!(interner.intern("b"), 0);
+        assert_eq!(interner.intern("a"), 1);
+        assert_eq!(interner.intern("b"), 0);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get(&"a"), Some(1));
+        assert_eq!(interner.get(&"c"), None);
+        assert_eq!(*interner.resolve(1), "a");
+    }
+}
@@ -1,3 +1,21 @@
+pub mod bench;
+pub mod bitset;
+pub mod cycle;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod intern;
+pub mod normalize;
+pub mod parse;
+pub mod parser;
+mod solution;
+
+pub use bench::{bench, black_box, BenchConfig, BenchStats};
+pub use input::{InputLocator, InputSource};
+pub use parse::ParseError;
+pub use solution::{solve, solve_with, Part, PartAnswer, Solution, SolveReport};
+
 use std::time::{Duration, Instant};
 
 pub struct Timed<T> {
@@ -0,0 +1,84 @@
+//! Tolerant handling of the line structure of puzzle inputs, so that inputs with CRLF line
+//! endings, trailing whitespace or trailing newlines parse the same as clean ones. [`lines`] and
+//! [`sections`] return subslices of their input so that [`ParseError`](crate::ParseError)
+//! locations stay accurate; [`normalize`] only allocates a cleaned up copy when the input needs
+//! one.
+
+use std::borrow::Cow;
+
+/// Converts CRLF line endings to LF, strips trailing whitespace from every line and removes
+/// trailing blank lines. Borrows the input when it is already normalised.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.trim_end();
+    if input.contains('\r') || lines(input).any(|line| line.len() != line.trim_end().len()) {
+        Cow::Owned(lines(input).collect::<Vec<_>>().join("\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Iterates over the lines of `input` with trailing whitespace (including `\r`) removed,
+/// ignoring any blank lines at the end of the input.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    input.trim_end().lines().map(str::trim_end)
+}
+
+/// Iterates over the sections of `input`, i.e. the blocks of lines separated by one or more
+/// blank (or whitespace only) lines.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input.trim_end();
+    std::iter::from_fn(move || {
+        // skip the blank lines before the section
+        while let Some((line, tail)) = rest.split_once('\n') {
+            if !line.trim().is_empty() {
+                break;
+            }
+            rest = tail;
+        }
+        if rest.trim().is_empty() {
+            return None;
+        }
+        let mut end = rest.len();
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                end = offset;
+                break;
+            }
+            offset += line.len();
+        }
+        let (section, tail) = rest.split_at(end);
+        rest = tail;
+        Some(section.trim_end())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("a\nb"), Cow::Borrowed("a\nb")));
+        assert!(matches!(normalize("a\nb\n\n"), Cow::Borrowed("a\nb")));
+        assert_eq!(normalize("a \r\nb\r\n\r\n"), "a\nb");
+        assert_eq!(normalize("a\r\n\r\nb\t"), "a\n\nb");
+    }
+
+    #[test]
+    fn test_lines() {
+        let lines = lines("a\r\nb  \n\nc\n\n").collect::<Vec<_>>();
+
+        assert_eq!(lines, vec!["a", "b", "", "c"]);
+        assert_eq!(super::lines(" \n").count(), 0);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "\na\nb\n\n \n\t\nc\r\n\r\nd\n";
+
+        assert_eq!(sections(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+        assert_eq!(sections("").count(), 0);
+        assert_eq!(sections("x").collect::<Vec<_>>(), vec!["x"]);
+    }
+}
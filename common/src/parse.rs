@@ -0,0 +1,109 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A parse failure pointing at the offending part of the puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number, counted in characters
+    pub column: usize,
+    /// The full line the error occurred on
+    pub snippet: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error located at the byte `offset` of `input`.
+    pub fn at_offset(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |idx| offset + idx);
+        ParseError {
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error located at `fragment`, which must be a subslice of `input`.
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        Self::at_offset(input, offset_of(input, fragment), message)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        writeln!(f, "  | {}", self.snippet)?;
+        write!(f, "  | {:>width$}", "^", width = self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Byte offset of `fragment` within `input`. Falls back to the end of `input` when `fragment`
+/// isn't a subslice of it.
+pub fn offset_of(input: &str, fragment: &str) -> usize {
+    let start = input.as_ptr() as usize;
+    let fragment_start = fragment.as_ptr() as usize;
+    if (start..=start + input.len()).contains(&fragment_start) {
+        fragment_start - start
+    } else {
+        debug_assert!(false, "fragment is not part of the input");
+        input.len()
+    }
+}
+
+/// Parses `token`, a subslice of `input`, reporting failures at the location of the token.
+pub fn parse_at<T>(input: &str, token: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token
+        .parse()
+        .map_err(|e| ParseError::at(input, token, format!("invalid value `{token}`: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let input = "1 2\n3 x4\n5 6";
+        let err = ParseError::at(input, &input[6..8], "bad token");
+
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 3);
+        assert_eq!(err.snippet, "3 x4");
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: bad token\n  | 3 x4\n  |   ^"
+        );
+
+        let err = ParseError::at_offset(input, input.len(), "unexpected end of input");
+        assert_eq!((err.line, err.column), (3, 4));
+    }
+
+    #[test]
+    fn test_parse_at() {
+        let input = "12,1x";
+
+        assert_eq!(parse_at::<u8>(input, &input[..2]), Ok(12));
+        let err = parse_at::<u8>(input, &input[3..]).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 4));
+        assert!(err.message.starts_with("invalid value `1x`"));
+    }
+}
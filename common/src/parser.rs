@@ -0,0 +1,346 @@
+//! A small zero-copy parser-combinator toolkit for puzzle inputs.
+//!
+//! A parser is anything implementing [`Parser`], most commonly a closure taking the remaining
+//! input and returning the parsed value along with the input left after it. Parsers only ever
+//! hand out subslices of their input, which is what lets [`parse_all`] turn a failure into a
+//! [`ParseError`] pointing at the exact line and column of the original input.
+
+use crate::grid::Grid;
+use crate::normalize;
+use crate::parse::ParseError;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A failed parse: where in the input it happened and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    pub at: &'a str,
+    pub message: String,
+}
+
+impl<'a> Failure<'a> {
+    pub fn new(at: &'a str, message: impl Into<String>) -> Self {
+        Failure {
+            at,
+            message: message.into(),
+        }
+    }
+}
+
+/// The parsed value and the remaining input, or the reason parsing failed.
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+pub trait Parser<'a, T> {
+    fn parse(&mut self, input: &'a str) -> PResult<'a, T>;
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: FnMut(&'a str) -> PResult<'a, T>,
+{
+    fn parse(&mut self, input: &'a str) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Runs `parser` over the whole of `input`, failing if anything but whitespace is left over.
+pub fn parse_all<'a, T>(input: &'a str, parser: impl Parser<'a, T>) -> Result<T, ParseError> {
+    consume_all(input, parser).map_err(|failure| ParseError::at(input, failure.at, failure.message))
+}
+
+fn consume_all<'a, T>(input: &'a str, mut parser: impl Parser<'a, T>) -> Result<T, Failure<'a>> {
+    let (value, rest) = parser.parse(input)?;
+    let trailing = rest.trim_start();
+    if !trailing.is_empty() {
+        return Err(Failure::new(trailing, "unexpected trailing input"));
+    }
+    Ok(value)
+}
+
+/// Matches the exact `token`.
+pub fn literal<'a>(token: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(token) {
+        Some(rest) => Ok((&input[..token.len()], rest)),
+        None => Err(Failure::new(input, format!("expected `{token}`"))),
+    }
+}
+
+/// Matches an integer with an optional `+`/`-` sign and converts it with `T::from_str`, so that
+/// out of range values and signs on unsigned types are reported as failures.
+pub fn integer<'a, T>() -> impl Parser<'a, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    move |input: &'a str| {
+        let sign_len = usize::from(input.starts_with(['+', '-']));
+        let digits_len = input[sign_len..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if digits_len == 0 {
+            return Err(Failure::new(input, "expected an integer"));
+        }
+        let (token, rest) = input.split_at(sign_len + digits_len);
+        let value = token
+            .parse()
+            .map_err(|e| Failure::new(input, format!("invalid value `{token}`: {e}")))?;
+        Ok((value, rest))
+    }
+}
+
+/// Matches one or more characters satisfying `predicate`.
+pub fn take_while1<'a>(predicate: impl Fn(char) -> bool) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let len = input
+            .char_indices()
+            .find(|(_, c)| !predicate(*c))
+            .map_or(input.len(), |(idx, _)| idx);
+        match len {
+            0 => Err(Failure::new(input, "unexpected character")),
+            len => Ok(input.split_at(len)),
+        }
+    }
+}
+
+/// Converts the text matched by `parser` with `T::from_str`.
+pub fn from_str<'a, T>(mut parser: impl Parser<'a, &'a str>) -> impl Parser<'a, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    move |input: &'a str| {
+        let (token, rest) = parser.parse(input)?;
+        let value = token
+            .parse()
+            .map_err(|e| Failure::new(token, format!("invalid value `{token}`: {e}")))?;
+        Ok((value, rest))
+    }
+}
+
+/// Matches zero or more spaces or tabs.
+pub fn space0<'a>() -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let rest = input.trim_start_matches([' ', '\t']);
+        Ok((&input[..input.len() - rest.len()], rest))
+    }
+}
+
+/// Matches one or more spaces or tabs.
+pub fn space1<'a>() -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match space0().parse(input)? {
+        ("", _) => Err(Failure::new(input, "expected whitespace")),
+        matched => Ok(matched),
+    }
+}
+
+/// Runs `parser` allowing spaces or tabs on either side of it.
+pub fn padded<'a, T>(mut parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let (_, rest) = space0().parse(input)?;
+        let (value, rest) = parser.parse(rest)?;
+        let (_, rest) = space0().parse(rest)?;
+        Ok((value, rest))
+    }
+}
+
+/// Converts the output of `parser` with `f`.
+pub fn map<'a, T, U>(
+    mut parser: impl Parser<'a, T>,
+    mut f: impl FnMut(T) -> U,
+) -> impl Parser<'a, U> {
+    move |input: &'a str| {
+        let (value, rest) = parser.parse(input)?;
+        Ok((f(value), rest))
+    }
+}
+
+/// Parses `first`, `separator` and `second` in sequence, e.g. the `X|Y` of a `key|value` pair,
+/// keeping the outputs of `first` and `second`.
+pub fn separated_pair<'a, A, S, B>(
+    mut first: impl Parser<'a, A>,
+    mut separator: impl Parser<'a, S>,
+    mut second: impl Parser<'a, B>,
+) -> impl Parser<'a, (A, B)> {
+    move |input: &'a str| {
+        let (a, rest) = first.parse(input)?;
+        let (_, rest) = separator.parse(rest)?;
+        let (b, rest) = second.parse(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+/// Parses one or more `item`s separated by `separator`. Stops before the first separator that
+/// can't be matched; an item that fails to parse after a separator is reported as a failure.
+pub fn separated<'a, T, S>(
+    mut item: impl Parser<'a, T>,
+    mut separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((_, after_separator)) = separator.parse(rest) {
+            let (value, after_item) = item.parse(after_separator)?;
+            items.push(value);
+            rest = after_item;
+        }
+        Ok((items, rest))
+    }
+}
+
+/// Parses every non-blank line of the input, ignoring leading and trailing whitespace, with
+/// `line`, which must consume the whole line. Consumes the whole input.
+pub fn lines_of<'a, T>(mut line: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str| {
+        let items = normalize::lines(input)
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| consume_all(l, |l| line.parse(l)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((items, &input[input.len()..]))
+    }
+}
+
+/// Parses an input made of exactly two [sections](normalize::sections), parsing each one in
+/// full with the respective parser. A missing second section is parsed as empty input.
+pub fn section_pair<'a, A, B>(
+    mut first: impl Parser<'a, A>,
+    mut second: impl Parser<'a, B>,
+) -> impl Parser<'a, (A, B)> {
+    move |input: &'a str| {
+        let end = &input[input.len()..];
+        let mut sections = normalize::sections(input);
+        let a = consume_all(sections.next().unwrap_or(end), |s| first.parse(s))?;
+        let b = consume_all(sections.next().unwrap_or(end), |s| second.parse(s))?;
+        if let Some(section) = sections.next() {
+            return Err(Failure::new(section, "expected only two sections"));
+        }
+        Ok(((a, b), end))
+    }
+}
+
+/// Parses a rectangular grid made of the lines up to the next blank line (or the end of the
+/// input), mapping every character through `parse_cell`.
+pub fn grid<'a, T, E>(mut parse_cell: impl FnMut(char) -> Result<T, E>) -> impl Parser<'a, Grid<T>>
+where
+    E: Display,
+{
+    move |input: &'a str| {
+        let mut width = None;
+        let mut cells = Vec::with_capacity(input.len());
+        let mut height = 0;
+        let mut rest = input;
+        while !rest.is_empty() {
+            let (line, tail) = rest.split_once('\n').unwrap_or((rest, &rest[rest.len()..]));
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let len_before = cells.len();
+            for (idx, c) in line.char_indices() {
+                let cell = parse_cell(c).map_err(|e| Failure::new(&line[idx..], e.to_string()))?;
+                cells.push(cell);
+            }
+            let row_width = cells.len() - len_before;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    let message = format!("row has width {row_width} but expected {width}");
+                    return Err(Failure::new(&line[line.len()..], message));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+            rest = tail;
+        }
+        let grid = Grid::new(width.unwrap_or_default(), height, cells)
+            .map_err(|e| Failure::new(input, e.to_string()))?;
+        Ok((grid, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(integer::<i32>().parse("-12,3"), Ok((-12, ",3")));
+        assert_eq!(integer::<u8>().parse("+7"), Ok((7, "")));
+
+        let err = parse_all("1\n 300", lines_of(integer::<u8>())).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(err.message.starts_with("invalid value `300`"));
+        assert!(parse_all("-1", integer::<u8>()).is_err());
+        assert!(parse_all("x", integer::<u8>()).is_err());
+    }
+
+    #[test]
+    fn test_take_while1_and_from_str() {
+        let mut word = take_while1(char::is_alphanumeric);
+        assert_eq!(word.parse("ab1,c"), Ok(("ab1", ",c")));
+        assert!(word.parse(",c").is_err());
+
+        let mut label = from_str::<u8>(take_while1(|c| c != ','));
+        assert_eq!(label.parse("42,1"), Ok((42, ",1")));
+        let err = parse_all("1000", label).unwrap_err();
+        assert!(err.message.starts_with("invalid value `1000`"));
+    }
+
+    #[test]
+    fn test_separated() {
+        let mut list = separated(integer::<u8>(), padded(literal(",")));
+        assert_eq!(list.parse("1, 2 ,3;"), Ok((vec![1, 2, 3], ";")));
+
+        let err = parse_all("1,2,x", list).unwrap_err();
+        assert_eq!(err.column, 5);
+    }
+
+    #[test]
+    fn test_separated_pair() {
+        let mut pair = separated_pair(integer::<u8>(), literal("|"), integer::<u8>());
+        assert_eq!(pair.parse("47|53"), Ok(((47, 53), "")));
+
+        let err = parse_all("47-53", pair).unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (3, "expected `|`"));
+    }
+
+    #[test]
+    fn test_section_pair() -> anyhow::Result<()> {
+        let rules = lines_of(separated_pair(integer::<u8>(), literal("|"), integer()));
+        let lists = lines_of(separated(integer::<u8>(), literal(",")));
+        let ((rules, lists), _) = section_pair(rules, lists)
+            .parse("1|2\n3|4\n\n5,6\n")
+            .map_err(|failure| anyhow::anyhow!(failure.message))?;
+
+        assert_eq!(rules, vec![(1, 2), (3, 4)]);
+        assert_eq!(lists, vec![vec![5, 6]]);
+
+        let parser = section_pair(lines_of(integer::<u8>()), lines_of(integer::<u8>()));
+        let err = parse_all("1\n\n2\n\n3", parser).unwrap_err();
+        assert_eq!(err.line, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut parser = grid(|c| c.to_digit(10).ok_or("not a digit"));
+        let (grid, rest) = parser.parse("12\n34\n\nrest").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid[(1, 0)], 3);
+        assert_eq!(rest, "\nrest");
+    }
+
+    #[test]
+    fn test_map_and_space() {
+        let mut parser = map(
+            separated_pair(integer::<u8>(), space1(), integer::<u8>()),
+            |(a, b)| a + b,
+        );
+
+        assert_eq!(parser.parse("1 \t2"), Ok((3, "")));
+        assert!(parse_all("12", parser).is_err());
+    }
+}
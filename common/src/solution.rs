@@ -0,0 +1,158 @@
+use crate::normalize::normalize;
+use crate::time;
+use anyhow::bail;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// The contract every puzzle day implements: parse the raw input once and then solve each
+/// part against the parsed representation.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> anyhow::Result<impl Display>;
+
+    fn part2(input: &Self::Input) -> anyhow::Result<impl Display>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => bail!("invalid part: {}", value),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", u8::from(*self))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartAnswer {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Outcome of solving a day along with the time spent in each stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveReport {
+    pub parse_elapsed: Duration,
+    pub answers: Vec<PartAnswer>,
+}
+
+impl SolveReport {
+    pub fn answer(&self, part: Part) -> Option<&PartAnswer> {
+        self.answers.iter().find(|answer| answer.part == part)
+    }
+
+    pub fn total_elapsed(&self) -> Duration {
+        self.parse_elapsed
+            + self
+                .answers
+                .iter()
+                .map(|answer| answer.elapsed)
+                .sum::<Duration>()
+    }
+}
+
+/// Parses the [normalised](normalize) `input` for the solution `S` and runs the requested `parts`
+/// against it, timing the parse stage and every part separately. Answers are reported in the
+/// order requested.
+pub fn solve<S: Solution>(input: &str, parts: &[Part]) -> anyhow::Result<SolveReport> {
+    solve_with::<S>(|| S::parse(&normalize(input)), parts)
+}
+
+/// Like [`solve`], but parses the input with `parse` instead of [`Solution::parse`], e.g. to
+/// parse it straight from a reader.
+pub fn solve_with<S: Solution>(
+    parse: impl FnOnce() -> anyhow::Result<S::Input>,
+    parts: &[Part],
+) -> anyhow::Result<SolveReport> {
+    let parsed = time(parse);
+    let parse_elapsed = parsed.elapsed();
+    let parsed = parsed.output?;
+    let answers = parts
+        .iter()
+        .map(|part| {
+            let answer = time(|| match part {
+                Part::One => S::part1(&parsed).map(|answer| answer.to_string()),
+                Part::Two => S::part2(&parsed).map(|answer| answer.to_string()),
+            });
+            Ok(PartAnswer {
+                part: *part,
+                elapsed: answer.elapsed(),
+                answer: answer.output?,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(SolveReport {
+        parse_elapsed,
+        answers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> anyhow::Result<Self::Input> {
+            Ok(input.split(',').map(str::parse).collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> anyhow::Result<impl Display> {
+            Ok(input.iter().sum::<u32>())
+        }
+
+        fn part2(input: &Self::Input) -> anyhow::Result<impl Display> {
+            Ok(input.iter().product::<u32>())
+        }
+    }
+
+    #[test]
+    fn test_solve() -> anyhow::Result<()> {
+        let report = solve::<Sum>("2,3,4", &[Part::Two, Part::One])?;
+        let answers = report
+            .answers
+            .iter()
+            .map(|answer| (answer.part, answer.answer.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(answers, vec![(Part::Two, "24"), (Part::One, "9")]);
+        assert!(report.answer(Part::One).unwrap().elapsed <= report.total_elapsed());
+        assert!(solve::<Sum>("2,x", &Part::ALL).is_err());
+
+        Ok(())
+    }
+}